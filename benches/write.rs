@@ -0,0 +1,50 @@
+//! `CsvWriter::write_data` against `write_data_parallel`, on a table of
+//! mixed values like a trades export:
+//!
+//! ```sh
+//! cargo bench --bench write
+//! ```
+
+use std::{hint::black_box, io};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use custom_errors::csvreader::{CsvData, CsvWriter, Value};
+
+fn table(rows: usize) -> CsvData<Value> {
+    let header = ["id", "symbol", "price", "quantity", "settled", "note"];
+    let data = (0..rows)
+        .map(|i| {
+            vec![
+                Value::Int(i as i64),
+                Value::Text(format!("SYM{}", i % 500)),
+                Value::Float(i as f64 * 1.25 + 0.1),
+                Value::Int((i % 1000) as i64),
+                Value::Bool(i % 3 == 0),
+                Value::Text(if i % 10 == 0 { "late, \"manual\"" } else { "" }.into()),
+            ]
+        })
+        .collect();
+    CsvData {
+        header: header.map(String::from).to_vec(),
+        data,
+    }
+}
+
+fn write(c: &mut Criterion) {
+    let mut group = c.benchmark_group("write");
+    group.sample_size(10);
+    for rows in [10_000, 1_000_000] {
+        let data = table(rows);
+        group.throughput(Throughput::Elements(rows as u64));
+        group.bench_with_input(BenchmarkId::new("serial", rows), &data, |b, data| {
+            b.iter(|| CsvWriter::new(io::sink()).write_data(black_box(data)))
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", rows), &data, |b, data| {
+            b.iter(|| CsvWriter::new(io::sink()).write_data_parallel(black_box(data)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, write);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+/* C interface to the CSV reader, see csvreader-ffi/src/lib.rs. */
+#ifndef CSVREADER_H
+#define CSVREADER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct CsvTable CsvTable;
+typedef struct CsvError CsvError;
+
+/* Reads `path`. `delimiter` is a Unicode scalar value, 0 for ','. Returns
+ * NULL on failure, storing an error in `*error` unless `error` is NULL. */
+CsvTable *csv_read_file(const char *path, uint32_t delimiter, int has_header, CsvError **error);
+void csv_table_free(CsvTable *table);
+
+size_t csv_table_columns(const CsvTable *table);
+size_t csv_table_rows(const CsvTable *table);
+/* NULL if out of range. Strings live as long as the table. */
+const char *csv_table_header(const CsvTable *table, size_t column);
+const char *csv_table_value(const CsvTable *table, size_t row, size_t column);
+
+void csv_error_free(CsvError *error);
+const char *csv_error_code(const CsvError *error);
+const char *csv_error_message(const CsvError *error);
+int csv_error_exit_code(const CsvError *error);
+/* 1-based, 0 if the problem isn't tied to a line or field. */
+size_t csv_error_line(const CsvError *error);
+size_t csv_error_column(const CsvError *error);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
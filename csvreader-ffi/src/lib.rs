@@ -0,0 +1,324 @@
+//! A C interface to the reader, declared in `include/csvreader.h`. Tables
+//! and errors are opaque handles, freed with `csv_table_free` and
+//! `csv_error_free`; the strings they hand out live as long as they do.
+//!
+//! ```c
+//! CsvError *error = NULL;
+//! CsvTable *table = csv_read_file("trades.csv", ',', 1, &error);
+//! if (!table) {
+//!     fprintf(stderr, "%s: %s\n", csv_error_code(error), csv_error_message(error));
+//!     csv_error_free(error);
+//!     return 1;
+//! }
+//! for (size_t row = 0; row < csv_table_rows(table); row++)
+//!     puts(csv_table_value(table, row, 0));
+//! csv_table_free(table);
+//! ```
+
+use std::{
+    ffi::{c_char, c_int, CStr, CString},
+    path::PathBuf,
+    ptr,
+};
+
+use custom_errors::csvreader::{self, read_csv_flat_with_options, Catalog, CsvReadOptions};
+
+/// The records of a file, as text.
+pub struct CsvTable {
+    header: Vec<CString>,
+    values: Vec<CString>,
+}
+
+/// A problem that stopped a read.
+pub struct CsvError {
+    code: CString,
+    message: CString,
+    exit_code: c_int,
+    line: usize,
+    column: usize,
+}
+
+impl From<&csvreader::CsvError> for CsvError {
+    fn from(e: &csvreader::CsvError) -> Self {
+        Self {
+            code: c_string(e.code()),
+            message: c_string(&e.localize(Catalog::english())),
+            exit_code: e.category().exit_code(),
+            line: e.line_num().unwrap_or(0),
+            column: e.column().map_or(0, |column| column + 1),
+        }
+    }
+}
+
+/// `text` up to its first NUL, if it has one.
+fn c_string(text: &str) -> CString {
+    let end = text.find('\0').unwrap_or(text.len());
+    CString::new(&text[..end]).unwrap_or_default()
+}
+
+/// Reads `path`, whose fields are separated by `delimiter` (a Unicode
+/// scalar value, `0` for `,`), and whose first line is a header unless
+/// `has_header` is `0`. Returns `NULL` on failure, and then stores an error
+/// in `*error` unless `error` is `NULL`.
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string, and `error` `NULL` or valid to
+/// write a pointer to.
+#[no_mangle]
+pub unsafe extern "C" fn csv_read_file(
+    path: *const c_char,
+    delimiter: u32,
+    has_header: c_int,
+    error: *mut *mut CsvError,
+) -> *mut CsvTable {
+    let fail = |e: &csvreader::CsvError| {
+        if !error.is_null() {
+            *error = Box::into_raw(Box::new(CsvError::from(e)));
+        }
+        ptr::null_mut()
+    };
+    if path.is_null() {
+        return fail(&csvreader::CsvError::FileNotFound(PathBuf::new()));
+    }
+    let path = CStr::from_ptr(path).to_string_lossy();
+    let mut options = CsvReadOptions::new().has_header(has_header != 0);
+    if let Some(delimiter) = char::from_u32(delimiter).filter(|&c| c != '\0') {
+        options = options.delimiter(delimiter);
+    }
+    match read_csv_flat_with_options::<String>(&path, &options) {
+        Ok((data, _)) => Box::into_raw(Box::new(CsvTable {
+            header: data.header.iter().map(|name| c_string(name)).collect(),
+            values: data.values.iter().map(|value| c_string(value)).collect(),
+        })),
+        Err(e) => fail(&e),
+    }
+}
+
+/// # Safety
+///
+/// `table` must be `NULL` or come from `csv_read_file`, and not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn csv_table_free(table: *mut CsvTable) {
+    if !table.is_null() {
+        drop(Box::from_raw(table));
+    }
+}
+
+/// Number of columns.
+///
+/// # Safety
+///
+/// `table` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_table_columns(table: *const CsvTable) -> usize {
+    (*table).header.len()
+}
+
+/// Number of records, not counting the header.
+///
+/// # Safety
+///
+/// `table` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_table_rows(table: *const CsvTable) -> usize {
+    let table = &*table;
+    table
+        .values
+        .len()
+        .checked_div(table.header.len())
+        .unwrap_or(0)
+}
+
+/// The name of `column`, or `NULL` if there is no such column.
+///
+/// # Safety
+///
+/// `table` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_table_header(table: *const CsvTable, column: usize) -> *const c_char {
+    let table = &*table;
+    table
+        .header
+        .get(column)
+        .map_or(ptr::null(), |name| name.as_ptr())
+}
+
+/// The value of `column` in record `row`, both counting from 0, or `NULL`
+/// if there is no such field.
+///
+/// # Safety
+///
+/// `table` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_table_value(
+    table: *const CsvTable,
+    row: usize,
+    column: usize,
+) -> *const c_char {
+    let table = &*table;
+    let width = table.header.len();
+    if column >= width {
+        return ptr::null();
+    }
+    row.checked_mul(width)
+        .and_then(|start| start.checked_add(column))
+        .and_then(|i| table.values.get(i))
+        .map_or(ptr::null(), |value| value.as_ptr())
+}
+
+/// # Safety
+///
+/// `error` must be `NULL` or come from `csv_read_file`, and not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn csv_error_free(error: *mut CsvError) {
+    if !error.is_null() {
+        drop(Box::from_raw(error));
+    }
+}
+
+/// A stable, machine-readable name for the kind of problem, such as
+/// `file_not_found`.
+///
+/// # Safety
+///
+/// `error` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_error_code(error: *const CsvError) -> *const c_char {
+    (*error).code.as_ptr()
+}
+
+/// The problem, in English.
+///
+/// # Safety
+///
+/// `error` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_error_message(error: *const CsvError) -> *const c_char {
+    (*error).message.as_ptr()
+}
+
+/// The process exit code for the kind of problem, as `csvtool` uses it.
+///
+/// # Safety
+///
+/// `error` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_error_exit_code(error: *const CsvError) -> c_int {
+    (*error).exit_code
+}
+
+/// The 1-based line of the problem, or 0 if it isn't tied to one.
+///
+/// # Safety
+///
+/// `error` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_error_line(error: *const CsvError) -> usize {
+    (*error).line
+}
+
+/// The 1-based field of the problem, or 0 if it isn't tied to one.
+///
+/// # Safety
+///
+/// `error` must come from `csv_read_file`.
+#[no_mangle]
+pub unsafe extern "C" fn csv_error_column(error: *const CsvError) -> usize {
+    (*error).column
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    /// A file in the temporary directory holding `contents`, and its path
+    /// as a C string.
+    fn file(name: &str, contents: &str) -> (PathBuf, CString) {
+        let path = env::temp_dir().join(format!("csvreader-ffi-{}-{}", process::id(), name));
+        fs::write(&path, contents).unwrap();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        (path, c_path)
+    }
+
+    unsafe fn text<'a>(ptr: *const c_char) -> Option<&'a str> {
+        (!ptr.is_null()).then(|| CStr::from_ptr(ptr).to_str().unwrap())
+    }
+
+    #[test]
+    fn reads_a_table() {
+        let (path, c_path) = file("table.csv", "a;b\n1;2\n3;4\n");
+        unsafe {
+            let mut error = ptr::null_mut();
+            let table = csv_read_file(c_path.as_ptr(), ';' as u32, 1, &mut error);
+            assert!(!table.is_null());
+            assert!(error.is_null());
+            assert_eq!(csv_table_columns(table), 2);
+            assert_eq!(csv_table_rows(table), 2);
+            assert_eq!(text(csv_table_header(table, 1)), Some("b"));
+            assert_eq!(text(csv_table_header(table, 2)), None);
+            assert_eq!(text(csv_table_value(table, 0, 0)), Some("1"));
+            assert_eq!(text(csv_table_value(table, 1, 1)), Some("4"));
+            assert_eq!(text(csv_table_value(table, 2, 0)), None);
+            assert_eq!(text(csv_table_value(table, 0, 2)), None);
+            assert_eq!(text(csv_table_value(table, usize::MAX, 1)), None);
+            csv_table_free(table);
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reads_without_a_header() {
+        let (path, c_path) = file("records.csv", "1,2\n3,4\n");
+        unsafe {
+            let table = csv_read_file(c_path.as_ptr(), 0, 0, ptr::null_mut());
+            assert_eq!(csv_table_rows(table), 2);
+            assert_eq!(text(csv_table_value(table, 0, 1)), Some("2"));
+            csv_table_free(table);
+        }
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reports_errors() {
+        let missing = CString::new("/nonexistent/csvreader-ffi.csv").unwrap();
+        unsafe {
+            let mut error = ptr::null_mut();
+            assert!(csv_read_file(missing.as_ptr(), 0, 1, &mut error).is_null());
+            assert_eq!(text(csv_error_code(error)), Some("file_not_found"));
+            assert!(text(csv_error_message(error))
+                .unwrap()
+                .contains("csvreader-ffi.csv"));
+            assert_eq!(csv_error_line(error), 0);
+            assert_eq!(csv_error_column(error), 0);
+            assert_ne!(csv_error_exit_code(error), 0);
+            csv_error_free(error);
+
+            let mut error = ptr::null_mut();
+            assert!(csv_read_file(ptr::null(), 0, 1, &mut error).is_null());
+            assert_eq!(text(csv_error_code(error)), Some("file_not_found"));
+            csv_error_free(error);
+
+            // Without somewhere to store it, the error is dropped.
+            assert!(csv_read_file(missing.as_ptr(), 0, 1, ptr::null_mut()).is_null());
+            csv_table_free(ptr::null_mut());
+            csv_error_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn reports_the_line_of_a_bad_record() {
+        let (path, c_path) = file("ragged.csv", "a,b\n1,2\n3\n");
+        unsafe {
+            let mut error = ptr::null_mut();
+            assert!(csv_read_file(c_path.as_ptr(), 0, 1, &mut error).is_null());
+            assert_eq!(csv_error_line(error), 3);
+            csv_error_free(error);
+        }
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,261 @@
+//! Python bindings for the reader, as the `csvreader` module:
+//!
+//! ```python
+//! import csvreader
+//!
+//! table = csvreader.read_csv("trades.csv", config="trades.toml")
+//! report = csvreader.validate("trades.csv", config="trades.toml")
+//! for problem in report.errors:
+//!     print(problem.line, problem.code, problem.message)
+//! ```
+//!
+//! A config file holds the same reader options and schema as
+//! [`CsvConfig`] does for Rust callers, so both apply the same rules.
+
+use custom_errors::csvreader::{
+    read_csv_validated_with_options, read_csv_validated_with_schema, read_csv_with_options,
+    read_csv_with_schema_options, CsvConfig, CsvData, CsvError, CsvErrorReport, Severity, Value,
+};
+use pyo3::{create_exception, exceptions::PyException, prelude::*, IntoPyObjectExt};
+
+create_exception!(
+    csvreader,
+    CsvReadError,
+    PyException,
+    "A problem that stopped a read. `args[1]` is the `Problem`."
+);
+
+/// One problem found in a file.
+#[pyclass(frozen, get_all)]
+struct Problem {
+    code: &'static str,
+    category: String,
+    severity: &'static str,
+    message: String,
+    path: Option<String>,
+    line: Option<usize>,
+    column: Option<usize>,
+    value: Option<String>,
+}
+
+#[pymethods]
+impl Problem {
+    fn __repr__(&self) -> String {
+        format!("<Problem {}: {}>", self.code, self.message)
+    }
+}
+
+impl From<&CsvError> for Problem {
+    fn from(e: &CsvError) -> Self {
+        Self {
+            code: e.code(),
+            category: format!("{:?}", e.category()).to_lowercase(),
+            severity: match e.severity() {
+                Severity::Warning => "warning",
+                Severity::Error => "error",
+            },
+            message: e.to_string(),
+            path: e.path().map(|path| path.display().to_string()),
+            line: e.line_num(),
+            column: e.column(),
+            value: e.value().map(String::from),
+        }
+    }
+}
+
+/// A record left out of the result, with the reason why.
+#[pyclass(frozen, get_all)]
+struct Skipped {
+    line: usize,
+    reason: Py<Problem>,
+    raw: String,
+}
+
+/// Everything that was found while reading a file.
+#[pyclass(frozen, get_all)]
+struct Report {
+    records: usize,
+    errors: Vec<Py<Problem>>,
+    warnings: Vec<Py<Problem>>,
+    skipped: Vec<Py<Skipped>>,
+    truncated: bool,
+}
+
+#[pymethods]
+impl Report {
+    /// Whether there are no errors.
+    #[getter]
+    fn ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<Report {} error(s), {} warning(s), {} skipped record(s)>",
+            self.errors.len(),
+            self.warnings.len(),
+            self.skipped.len()
+        )
+    }
+}
+
+impl Report {
+    fn new(py: Python<'_>, report: &CsvErrorReport) -> PyResult<Self> {
+        let problems = |errors: &[CsvError]| -> PyResult<Vec<Py<Problem>>> {
+            errors
+                .iter()
+                .map(|e| Py::new(py, Problem::from(e)))
+                .collect()
+        };
+        let skipped = report
+            .skipped
+            .iter()
+            .map(|record| {
+                let skipped = Skipped {
+                    line: record.line_num,
+                    reason: Py::new(py, Problem::from(&record.reason))?,
+                    raw: record.raw.clone(),
+                };
+                Py::new(py, skipped)
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(Self {
+            records: report.records,
+            errors: problems(&report.errors)?,
+            warnings: problems(&report.warnings)?,
+            skipped,
+            truncated: report.truncated,
+        })
+    }
+}
+
+/// The records of a file, each value an `int`, `float`, `bool`, `str` or
+/// `None`, with the problems that were recovered from.
+#[pyclass(frozen, get_all)]
+struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<PyObject>>,
+    report: Py<Report>,
+}
+
+#[pymethods]
+impl Table {
+    fn __len__(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<Table {} column(s), {} row(s)>",
+            self.header.len(),
+            self.rows.len()
+        )
+    }
+}
+
+impl Table {
+    fn new(py: Python<'_>, data: CsvData<Value>, report: &CsvErrorReport) -> PyResult<Self> {
+        let rows = data
+            .data
+            .into_iter()
+            .map(|row| row.into_iter().map(|value| to_py(py, value)).collect())
+            .collect::<PyResult<_>>()?;
+        Ok(Self {
+            header: data.header,
+            rows,
+            report: Py::new(py, Report::new(py, report)?)?,
+        })
+    }
+}
+
+fn to_py(py: Python<'_>, value: Value) -> PyResult<PyObject> {
+    match value {
+        Value::Null => Ok(py.None()),
+        Value::Bool(b) => b.into_py_any(py),
+        Value::Int(i) => i.into_py_any(py),
+        Value::Float(x) => x.into_py_any(py),
+        Value::Text(s) => s.into_py_any(py),
+    }
+}
+
+/// The `CsvReadError` for `e`.
+fn raise(py: Python<'_>, e: &CsvError) -> PyErr {
+    match Py::new(py, Problem::from(e)) {
+        Ok(problem) => CsvReadError::new_err((e.to_string(), problem)),
+        Err(err) => err,
+    }
+}
+
+/// The config at `path`, or the default, with the arguments given applied.
+fn config(
+    config: Option<&str>,
+    delimiter: Option<char>,
+    has_header: Option<bool>,
+) -> Result<CsvConfig, CsvError> {
+    let mut config = match config {
+        Some(path) => CsvConfig::from_file(path)?,
+        None => CsvConfig::default(),
+    };
+    let options = &mut config.options;
+    if let Some(delimiter) = delimiter {
+        options.delimiter = delimiter;
+    }
+    if let Some(has_header) = has_header {
+        options.has_header = has_header;
+    }
+    Ok(config)
+}
+
+/// Reads `path`, checked against the schema of `config` if it has one.
+/// Raises `CsvReadError` for the first problem the config's `on_error`
+/// doesn't skip.
+#[pyfunction]
+#[pyo3(signature = (path, *, config=None, delimiter=None, has_header=None))]
+fn read_csv(
+    py: Python<'_>,
+    path: &str,
+    config: Option<&str>,
+    delimiter: Option<char>,
+    has_header: Option<bool>,
+) -> PyResult<Table> {
+    let result =
+        self::config(config, delimiter, has_header).and_then(|config| match &config.schema {
+            Some(schema) => read_csv_with_schema_options(path, schema, &config.options),
+            None => read_csv_with_options(path, &config.options),
+        });
+    let (data, report) = result.map_err(|e| raise(py, &e))?;
+    Table::new(py, data, &report)
+}
+
+/// Reads all of `path`, checked against the schema of `config` if it has
+/// one, and reports every problem found. Raises `CsvReadError` only if the
+/// file can't be read at all.
+#[pyfunction]
+#[pyo3(signature = (path, *, config=None, delimiter=None, has_header=None))]
+fn validate(
+    py: Python<'_>,
+    path: &str,
+    config: Option<&str>,
+    delimiter: Option<char>,
+    has_header: Option<bool>,
+) -> PyResult<Report> {
+    let result =
+        self::config(config, delimiter, has_header).and_then(|config| match &config.schema {
+            Some(schema) => read_csv_validated_with_schema(path, schema, &config.options),
+            None => read_csv_validated_with_options::<Value>(path, &config.options),
+        });
+    let (_, report) = result.map_err(|e| raise(py, &e))?;
+    Report::new(py, &report)
+}
+
+#[pymodule]
+fn csvreader(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("CsvReadError", m.py().get_type::<CsvReadError>())?;
+    m.add_class::<Problem>()?;
+    m.add_class::<Skipped>()?;
+    m.add_class::<Report>()?;
+    m.add_class::<Table>()?;
+    m.add_function(wrap_pyfunction!(read_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,366 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use custom_errors::csvreader::{ColumnSelector, CsvReadOptions, JoinKind, Keep};
+
+/// Reads, checks and reshapes CSV files.
+#[derive(Debug, Parser)]
+#[command(name = "csvtool", version)]
+pub struct Cli {
+    /// How results and errors are printed.
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// For people: diagnostics on stderr.
+    Text,
+    /// For programs: one JSON document on stdout.
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the records of a file, reporting every problem found.
+    Read(ReadArgs),
+    /// Check a file or the `.csv` files in a directory, optionally against a
+    /// schema, and summarize the problems.
+    Validate(ValidateArgs),
+    /// Write a file in another format.
+    Convert(ConvertArgs),
+    /// Summarize every column: type, counts, range and distinct values.
+    Stats(InputArgs),
+    /// Write only some columns of a file, as CSV on stdout.
+    Select(SelectArgs),
+    /// Join two files on a key column, writing CSV to stdout.
+    Join(JoinArgs),
+    /// Write a uniform random sample of the records, as CSV on stdout.
+    Sample(SampleArgs),
+    /// Write the records sorted by a column, as CSV on stdout.
+    Sort(SortArgs),
+    /// Write the records without duplicates, as CSV on stdout.
+    Dedupe(DedupeArgs),
+    /// Write the records matching a filter, as CSV on stdout.
+    Query(QueryArgs),
+    /// Compare two versions of a file by a key column.
+    Diff(DiffArgs),
+    /// Infer a schema from a file, or check a file against one.
+    #[command(subcommand)]
+    Schema(SchemaCommand),
+    /// Print a file as an aligned table, with the type of each column.
+    Print(PrintArgs),
+    /// Print the first records of a file as a table.
+    Head(HeadArgs),
+    /// Print the last records of a file as a table.
+    Tail(HeadArgs),
+}
+
+/// The file to read and how it is laid out.
+#[derive(Debug, Args)]
+pub struct InputArgs {
+    /// The CSV file to read.
+    pub input: PathBuf,
+
+    /// Character separating the fields of a record [default: ,].
+    #[arg(short, long)]
+    pub delimiter: Option<char>,
+
+    /// The first line is a record, not a header.
+    #[arg(long)]
+    pub no_header: bool,
+}
+
+impl InputArgs {
+    /// `options` with the flags that were given applied on top.
+    pub fn apply(&self, mut options: CsvReadOptions) -> CsvReadOptions {
+        if let Some(delimiter) = self.delimiter {
+            options.delimiter = delimiter;
+        }
+        if self.no_header {
+            options.has_header = false;
+        }
+        options
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ReadArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Type every field is parsed as.
+    #[arg(short = 't', long = "type", value_enum, default_value_t = FieldType::I32)]
+    pub field_type: FieldType,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum FieldType {
+    I32,
+    I64,
+    F64,
+    String,
+    /// Infer the type of each field.
+    Auto,
+}
+
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// TOML or YAML file with reader options and a `[schema]` section.
+    #[arg(short, long)]
+    pub schema: Option<PathBuf>,
+
+    /// Keep running, checking files again whenever they or the schema
+    /// change.
+    #[arg(short, long)]
+    pub watch: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Format to write.
+    #[arg(long, value_enum)]
+    pub to: OutputFormat,
+
+    /// File to write instead of stdout.
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Field separator of the output, for `--to csv` [default: ,].
+    #[arg(long)]
+    pub out_delimiter: Option<char>,
+
+    /// Write the output to a temporary file and rename it over `--out`
+    /// once complete, so it is never left half written.
+    #[arg(long, requires = "out")]
+    pub atomic: bool,
+
+    /// Keep the previous version of `--out` as `<out>.bak`.
+    #[arg(long, requires = "out")]
+    pub backup: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Csv,
+    Tsv,
+    Json,
+    Jsonl,
+    /// Markdown table.
+    Md,
+}
+
+#[derive(Debug, Args)]
+pub struct HeadArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Number of records to print.
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub lines: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct SelectArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Comma-separated column names, 1-based positions or ranges such as
+    /// `2-4` or `5-`.
+    #[arg(short, long, value_delimiter = ',', required = true)]
+    pub columns: Vec<ColumnSelector>,
+}
+
+#[derive(Debug, Args)]
+pub struct JoinArgs {
+    #[command(flatten)]
+    pub left: InputArgs,
+
+    /// The file joined onto the first one, with the same layout.
+    pub right: PathBuf,
+
+    /// Column to join on, named the same in both files.
+    #[arg(long)]
+    pub on: String,
+
+    #[arg(long, value_enum, default_value_t = Kind::Inner)]
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Kind {
+    /// Keep only records with a match.
+    Inner,
+    /// Keep every record of the first file.
+    Left,
+}
+
+impl From<Kind> for JoinKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Inner => Self::Inner,
+            Kind::Left => Self::Left,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct PrintArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Print at most this many records.
+    #[arg(short = 'n', long)]
+    pub max_rows: Option<usize>,
+
+    /// When to highlight the header.
+    #[arg(long, value_enum, default_value_t = Color::Auto)]
+    pub color: Color,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Color {
+    /// When stdout is a terminal.
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Args)]
+pub struct SampleArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Number of records to pick.
+    #[arg(short = 'n', long, default_value_t = 100)]
+    pub size: usize,
+
+    /// Seed for a repeatable sample.
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct SortArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Column to sort by.
+    #[arg(long)]
+    pub by: String,
+
+    /// Largest first.
+    #[arg(long)]
+    pub desc: bool,
+
+    /// Compare as numbers rather than text.
+    #[arg(long)]
+    pub numeric: bool,
+
+    /// Files larger than this many MiB are sorted in chunks, through
+    /// temporary files.
+    #[arg(long, default_value_t = 256)]
+    pub memory_limit: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct DedupeArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Columns that identify a record [default: all of them].
+    #[arg(long, value_delimiter = ',')]
+    pub key: Vec<String>,
+
+    /// Which record of each duplicate key to keep.
+    #[arg(long, value_enum, default_value_t = KeepArg::First)]
+    pub keep: KeepArg,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    #[command(flatten)]
+    pub old: InputArgs,
+
+    /// The newer version of the file, with the same layout.
+    pub new: PathBuf,
+
+    /// Column that identifies a record, named the same in both files.
+    #[arg(long)]
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum KeepArg {
+    First,
+    Last,
+}
+
+impl From<KeepArg> for Keep {
+    fn from(keep: KeepArg) -> Self {
+        match keep {
+            KeepArg::First => Self::First,
+            KeepArg::Last => Self::Last,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Condition records must meet, such as "price > 100 && region == 'EU'".
+    #[arg(long = "where", value_name = "FILTER")]
+    pub filter: Option<String>,
+
+    /// Columns to write, by name, 1-based position or range like 2-4.
+    #[arg(long, value_delimiter = ',')]
+    pub select: Vec<ColumnSelector>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SchemaCommand {
+    /// Guess the type and range of each column, writing a schema file.
+    Infer(InferArgs),
+    /// Check a file, or the `.csv` files in a directory, against a schema.
+    Check(CheckArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct InferArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Where to write the schema, as YAML for `.yaml` and `.yml` files and
+    /// TOML otherwise [default: TOML on stdout].
+    #[arg(short, long)]
+    pub out: Option<PathBuf>,
+
+    /// Number of records to look at.
+    #[arg(long, default_value_t = 1000)]
+    pub sample_rows: usize,
+
+    /// Constrain numeric columns to the range of the sample. Later values
+    /// outside it then fail `schema check`.
+    #[arg(long)]
+    pub with_ranges: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// TOML or YAML file with a `[schema]` section.
+    pub schema: PathBuf,
+}
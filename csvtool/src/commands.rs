@@ -0,0 +1,126 @@
+use std::{fmt::Display, io, path::Path, process::exit, sync::OnceLock};
+
+use custom_errors::csvreader::{
+    render_diagnostic_with_delimiter, CsvData, CsvError, CsvErrorReport, CsvReadOptions, CsvWriter,
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::cli::Format;
+
+mod convert;
+mod dedupe;
+mod diff;
+mod head;
+mod join;
+mod print;
+mod query;
+mod read;
+mod sample;
+mod schema;
+mod select;
+mod sort;
+mod stats;
+mod validate;
+
+pub use convert::convert;
+pub use dedupe::dedupe;
+pub use diff::diff;
+pub use head::{head, tail};
+pub use join::join;
+pub use print::print;
+pub use query::query;
+pub use read::read;
+pub use sample::sample;
+pub use schema::schema;
+pub use select::select;
+pub use sort::sort;
+pub use stats::stats;
+pub use validate::validate;
+
+static FORMAT: OnceLock<Format> = OnceLock::new();
+
+/// Sets the `--format` for the rest of the run.
+pub fn set_format(format: Format) {
+    FORMAT.get_or_init(|| format);
+}
+
+fn json_output() -> bool {
+    FORMAT.get() == Some(&Format::Json)
+}
+
+fn print_json(value: &impl Serialize) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("error: could not write output: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Prints `e`, as `{ "error": ... }` on stdout with `--format json`.
+fn print_error(e: &CsvError) {
+    match json_output() {
+        true => print_json(&json!({ "error": e })),
+        false => eprintln!("error: {}", e),
+    }
+}
+
+/// Exits with the code for `e`'s category after printing it.
+fn fail(e: CsvError) -> ! {
+    print_error(&e);
+    exit(e.category().exit_code());
+}
+
+/// Exits if the output could not be written, e.g. to a closed pipe.
+fn check_write(result: io::Result<()>) {
+    if let Err(e) = result {
+        eprintln!("error: could not write output: {}", e);
+        exit(3);
+    }
+}
+
+/// Writes `data` to stdout as delimited text, laid out like the input it
+/// was read from with `options`: a file without a header gets none.
+fn write_csv<T: Display>(data: &CsvData<T>, options: &CsvReadOptions) {
+    let mut writer = CsvWriter::new(io::stdout().lock()).delimiter(options.delimiter);
+    let result = match options.has_header {
+        true => writer.write_data(data),
+        false => write_records(&mut writer, data),
+    };
+    check_write(result);
+}
+
+/// Writes the records of `data`, without the header.
+fn write_records<W: io::Write, T: Display>(
+    writer: &mut CsvWriter<W>,
+    data: &CsvData<T>,
+) -> io::Result<()> {
+    data.data
+        .iter()
+        .try_for_each(|row| writer.write_record(row))
+}
+
+/// The input path as the reader takes it.
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap_or_else(|| {
+        eprintln!("error: path is not valid UTF-8: {}", path.display());
+        exit(2);
+    })
+}
+
+/// Prints every error and warning in `report` next to its line of `path`.
+fn print_diagnostics(report: &CsvErrorReport, path: &str, delimiter: char) {
+    if report.is_empty() {
+        return;
+    }
+    let source = std::fs::read(path).unwrap_or_default();
+    let source = String::from_utf8_lossy(&source);
+    for error in report.errors.iter().chain(&report.warnings) {
+        eprintln!(
+            "{}\n",
+            render_diagnostic_with_delimiter(error, &source, delimiter)
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use std::io::{self, Write};
+
+use custom_errors::csvreader::{
+    read_csv_with_options, write_file, write_json, write_jsonl, write_markdown, CsvData,
+    CsvReadOptions, CsvWriter, Value, WriteOptions,
+};
+
+use super::{check_write, fail, path_str, write_records};
+use crate::cli::{ConvertArgs, OutputFormat};
+
+/// Reads the file with inferred types, so numbers and booleans come out as
+/// such in JSON, and writes it in the requested format.
+pub fn convert(args: &ConvertArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let (data, _) = read_csv_with_options::<Value>(path, &options).unwrap_or_else(|e| fail(e));
+
+    match &args.out {
+        Some(out) => {
+            let options = WriteOptions::new().atomic(args.atomic).backup(args.backup);
+            write_file(path_str(out), &options, |file| write(&data, args, file))
+                .unwrap_or_else(|e| fail(e));
+        }
+        None => check_write(write(&data, args, io::stdout().lock())),
+    }
+}
+
+fn write(data: &CsvData<Value>, args: &ConvertArgs, mut out: impl Write) -> io::Result<()> {
+    let delimiter = match args.to {
+        OutputFormat::Tsv => '\t',
+        _ => args.out_delimiter.unwrap_or(','),
+    };
+    match args.to {
+        // A file without a header is written without one too.
+        OutputFormat::Csv | OutputFormat::Tsv if args.input.no_header => {
+            write_records(&mut CsvWriter::new(&mut out).delimiter(delimiter), data)?
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => CsvWriter::new(&mut out)
+            .delimiter(delimiter)
+            .write_data_parallel(data)?,
+        OutputFormat::Json => write_json(data, &mut out)?,
+        OutputFormat::Jsonl => write_jsonl(data, &mut out)?,
+        OutputFormat::Md => write_markdown(data, &mut out)?,
+    }
+    out.flush()
+}
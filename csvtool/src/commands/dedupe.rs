@@ -0,0 +1,22 @@
+use custom_errors::csvreader::{read_csv_with_options, CsvReadOptions};
+
+use super::{fail, path_str, write_csv};
+use crate::cli::DedupeArgs;
+
+/// The records go to stdout and the count of dropped ones to stderr, so
+/// the output can be piped on.
+pub fn dedupe(args: &DedupeArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let (mut data, _) = read_csv_with_options::<String>(path, &options).unwrap_or_else(|e| fail(e));
+    let dropped = match args.key.is_empty() {
+        true => data.dedup(),
+        false => {
+            let key: Vec<&str> = args.key.iter().map(String::as_str).collect();
+            data.dedup_by(&key, args.keep.into())
+                .unwrap_or_else(|e| fail(e))
+        }
+    };
+    write_csv(&data, &options);
+    eprintln!("dropped {} duplicate record(s)", dropped);
+}
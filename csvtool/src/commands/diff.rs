@@ -0,0 +1,61 @@
+use std::process::exit;
+
+use custom_errors::csvreader::{read_csv_with_options, CsvDiff, CsvReadOptions};
+
+use super::{fail, json_output, path_str, print_json};
+use crate::cli::DiffArgs;
+
+/// Prints what changed from the first file to the second, one line per
+/// record: `- key` removed, `+ key` added, and `~ key: column "old" -> "new"`
+/// for each changed value, then a summary; the diff itself with
+/// `--format json`. Exits with 1 if the files differ, as `diff` does.
+pub fn diff(args: &DiffArgs) {
+    let options = args.old.apply(CsvReadOptions::new());
+    let read = |path| {
+        read_csv_with_options::<String>(path_str(path), &options)
+            .map(|(data, _)| data)
+            .unwrap_or_else(|e| fail(e))
+    };
+    let (old, new) = (read(&args.old.input), read(&args.new));
+    let diff = old.diff(&new, &args.key).unwrap_or_else(|e| fail(e));
+    match json_output() {
+        true => print_json(&diff),
+        false => print_text(&diff),
+    }
+    if !diff.is_empty() {
+        exit(1);
+    }
+}
+
+fn print_text(diff: &CsvDiff) {
+    for column in &diff.removed_columns {
+        println!("- column {}", column);
+    }
+    for column in &diff.added_columns {
+        println!("+ column {}", column);
+    }
+    let key = |header: &[String], row: &[String]| {
+        let i = header.iter().position(|name| *name == diff.key_column);
+        i.and_then(|i| row.get(i)).cloned().unwrap_or_default()
+    };
+    for row in &diff.removed.data {
+        println!("- {}", key(&diff.removed.header, row));
+    }
+    for row in &diff.added.data {
+        println!("+ {}", key(&diff.added.header, row));
+    }
+    for row in &diff.changed {
+        for change in &row.changes {
+            println!(
+                "~ {}: {} {:?} -> {:?}",
+                row.key, change.column, change.old, change.new
+            );
+        }
+    }
+    println!(
+        "{} removed, {} added, {} changed",
+        diff.removed.data.len(),
+        diff.added.data.len(),
+        diff.changed.len()
+    );
+}
@@ -0,0 +1,40 @@
+use std::collections::VecDeque;
+
+use custom_errors::csvreader::{stream_csv, CsvData, CsvReadOptions, Value};
+
+use super::{fail, path_str};
+use crate::cli::HeadArgs;
+
+/// Only the first `n` records are parsed.
+pub fn head(args: &HeadArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let mut records = stream_csv::<Value>(path, &options).unwrap_or_else(|e| fail(e));
+    let header = records.header().to_vec();
+    let data = records
+        .by_ref()
+        .take(args.lines)
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|e| fail(e));
+    print!("{}", CsvData { header, data });
+}
+
+/// Every record is parsed, but only the last `n` are kept.
+pub fn tail(args: &HeadArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let mut records = stream_csv::<Value>(path, &options).unwrap_or_else(|e| fail(e));
+    let header = records.header().to_vec();
+    let mut last = VecDeque::with_capacity(args.lines);
+    for record in records.by_ref() {
+        let record = record.unwrap_or_else(|e| fail(e));
+        if last.len() == args.lines {
+            last.pop_front();
+        }
+        if args.lines > 0 {
+            last.push_back(record);
+        }
+    }
+    let data = last.into();
+    print!("{}", CsvData { header, data });
+}
@@ -0,0 +1,18 @@
+use custom_errors::csvreader::{read_csv_with_options, CsvReadOptions};
+
+use super::{fail, path_str, write_csv};
+use crate::cli::JoinArgs;
+
+pub fn join(args: &JoinArgs) {
+    let options = args.left.apply(CsvReadOptions::new());
+    let read = |path| {
+        read_csv_with_options::<String>(path_str(path), &options)
+            .map(|(data, _)| data)
+            .unwrap_or_else(|e| fail(e))
+    };
+    let (left, right) = (read(&args.left.input), read(&args.right));
+    let joined = left
+        .join(&right, &args.on, args.kind.into())
+        .unwrap_or_else(|e| fail(e));
+    write_csv(&joined, &options);
+}
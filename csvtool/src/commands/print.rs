@@ -0,0 +1,29 @@
+use std::io::{self, IsTerminal};
+
+use custom_errors::csvreader::{read_csv_with_options, CsvReadOptions, Value};
+
+use super::{fail, path_str};
+use crate::cli::{Color, PrintArgs};
+
+/// The header shows each column's inferred type, e.g. `total (float)`.
+pub fn print(args: &PrintArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let (data, _) = read_csv_with_options::<Value>(path, &options).unwrap_or_else(|e| fail(e));
+
+    let labels = data
+        .describe()
+        .into_iter()
+        .map(|c| format!("{} ({})", c.name, c.column_type))
+        .collect();
+    let color = match args.color {
+        Color::Auto => io::stdout().is_terminal(),
+        Color::Always => true,
+        Color::Never => false,
+    };
+    let mut table = data.table().header(labels).color(color);
+    if let Some(max_rows) = args.max_rows {
+        table = table.max_rows(max_rows);
+    }
+    print!("{}", table);
+}
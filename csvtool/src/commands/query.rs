@@ -0,0 +1,21 @@
+use custom_errors::csvreader::{read_csv_with_options, CsvReadOptions, Filter};
+
+use super::{fail, path_str, write_csv};
+use crate::cli::QueryArgs;
+
+/// The filter sees every column, so the selection is applied after it, and
+/// the kept fields are copied as text.
+pub fn query(args: &QueryArgs) {
+    let filter = args.filter.as_deref().map(Filter::parse).transpose();
+    let filter = filter.unwrap_or_else(|e| fail(e));
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let (mut data, _) = read_csv_with_options::<String>(path, &options).unwrap_or_else(|e| fail(e));
+    if let Some(filter) = &filter {
+        data.filter(filter).unwrap_or_else(|e| fail(e));
+    }
+    if !args.select.is_empty() {
+        data = data.select(&args.select).unwrap_or_else(|e| fail(e));
+    }
+    write_csv(&data, &options);
+}
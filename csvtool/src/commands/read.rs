@@ -0,0 +1,34 @@
+use std::{fmt::Debug, process::exit, str::FromStr};
+
+use custom_errors::csvreader::{read_csv_validated_with_options, CsvReadOptions, Value};
+use serde::Serialize;
+use serde_json::json;
+
+use super::{fail, json_output, path_str, print_diagnostics, print_json};
+use crate::cli::{FieldType, ReadArgs};
+
+pub fn read(args: &ReadArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    match args.field_type {
+        FieldType::I32 => print::<i32>(args, &options),
+        FieldType::I64 => print::<i64>(args, &options),
+        FieldType::F64 => print::<f64>(args, &options),
+        FieldType::String => print::<String>(args, &options),
+        FieldType::Auto => print::<Value>(args, &options),
+    }
+}
+
+fn print<T: Default + FromStr + Debug + Serialize>(args: &ReadArgs, options: &CsvReadOptions) {
+    let path = path_str(&args.input.input);
+    let (csv_data, report) =
+        read_csv_validated_with_options::<T>(path, options).unwrap_or_else(|e| fail(e));
+    if json_output() {
+        print_json(&json!({ "data": csv_data, "report": report }));
+    } else {
+        println!("{:?}", csv_data);
+        print_diagnostics(&report, path, options.delimiter);
+    }
+    if let Some(category) = report.category() {
+        exit(category.exit_code());
+    }
+}
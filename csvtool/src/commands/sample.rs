@@ -0,0 +1,20 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use custom_errors::csvreader::{read_csv_sample_with_options, CsvReadOptions};
+
+use super::{fail, path_str, write_csv};
+use crate::cli::SampleArgs;
+
+/// See `read_csv_sample`: the file is read once and only the sample is kept
+/// in memory. Without `--seed` the sample differs from run to run.
+pub fn sample(args: &SampleArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let seed = args.seed.unwrap_or_else(|| {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH);
+        now.map_or(0, |d| d.as_nanos() as u64)
+    });
+    let (data, _) = read_csv_sample_with_options::<String>(path, args.size, seed, &options)
+        .unwrap_or_else(|e| fail(e));
+    write_csv(&data, &options);
+}
@@ -0,0 +1,57 @@
+use std::{fs, path::Path, process::exit};
+
+use custom_errors::csvreader::{
+    infer_schema_with_options, infer_schema_with_ranges, CsvConfig, CsvConfigError, CsvError,
+    CsvReadOptions,
+};
+
+use super::{
+    fail, path_str,
+    validate::{check_files, csv_files},
+};
+use crate::cli::{CheckArgs, InferArgs, SchemaCommand};
+
+pub fn schema(command: &SchemaCommand) {
+    match command {
+        SchemaCommand::Infer(args) => infer(args),
+        SchemaCommand::Check(args) => check(args),
+    }
+}
+
+fn infer(args: &InferArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let infer = match args.with_ranges {
+        true => infer_schema_with_ranges,
+        false => infer_schema_with_options,
+    };
+    let schema = infer(path, args.sample_rows, &options).unwrap_or_else(|e| fail(e));
+    let Some(out) = &args.out else {
+        print!("{}", schema.to_toml());
+        return;
+    };
+    let text = match out.extension().and_then(|e| e.to_str()) {
+        Some("yaml" | "yml") => schema.to_yaml(),
+        _ => schema.to_toml(),
+    };
+    if let Err(e) = fs::write(out, text) {
+        eprintln!("error: could not write {}: {}", out.display(), e);
+        exit(3);
+    }
+}
+
+/// Like `validate --schema`, but the file has to have a schema.
+fn check(args: &CheckArgs) {
+    let config = CsvConfig::from_file(&args.schema).unwrap_or_else(|e| fail(e));
+    if config.schema.is_none() {
+        fail(no_schema(&args.schema));
+    }
+    check_files(&csv_files(&args.input.input), &config, &args.input);
+}
+
+fn no_schema(path: &Path) -> CsvError {
+    CsvError::InvalidConfig(CsvConfigError {
+        path: path.to_path_buf(),
+        reason: "no [schema] section".into(),
+    })
+}
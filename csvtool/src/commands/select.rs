@@ -0,0 +1,15 @@
+use custom_errors::csvreader::{read_csv_with_options, CsvReadOptions};
+
+use super::{fail, path_str, write_csv};
+use crate::cli::SelectArgs;
+
+/// Fields are copied as text, so nothing is reformatted.
+pub fn select(args: &SelectArgs) {
+    let options = args
+        .input
+        .apply(CsvReadOptions::new())
+        .select(args.columns.clone());
+    let path = path_str(&args.input.input);
+    let (data, _) = read_csv_with_options::<String>(path, &options).unwrap_or_else(|e| fail(e));
+    write_csv(&data, &options);
+}
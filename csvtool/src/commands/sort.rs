@@ -0,0 +1,66 @@
+use std::{
+    collections::hash_map::RandomState,
+    env,
+    fs::{self, File, OpenOptions},
+    hash::{BuildHasher, Hasher},
+    io,
+    path::PathBuf,
+    process,
+};
+
+use custom_errors::csvreader::{
+    read_csv_with_options, sort_csv_file_with_options, CsvError, CsvFileError, CsvReadOptions,
+    SortOptions,
+};
+
+use super::{check_write, fail, path_str, write_csv};
+use crate::cli::SortArgs;
+
+/// A file that fits in `--memory-limit` is sorted in memory, with fields
+/// copied as text. A larger one is sorted into a temporary file, which is
+/// then copied to stdout.
+pub fn sort(args: &SortArgs) {
+    let options = args.input.apply(CsvReadOptions::new());
+    let path = path_str(&args.input.input);
+    let order = SortOptions::new()
+        .descending(args.desc)
+        .numeric(args.numeric)
+        .memory_limit(args.memory_limit << 20);
+    let size = fs::metadata(path).map_or(0, |metadata| metadata.len());
+    if size <= order.memory_limit as u64 {
+        let (mut data, _) =
+            read_csv_with_options::<String>(path, &options).unwrap_or_else(|e| fail(e));
+        data.sort_by_column(&args.by, order)
+            .unwrap_or_else(|e| fail(e));
+        write_csv(&data, &options);
+        return;
+    }
+    let sorted = scratch_file().unwrap_or_else(|e| fail(e));
+    let result = sort_csv_file_with_options(path, path_str(&sorted), &args.by, &options, order);
+    if let Err(e) = result {
+        let _ = fs::remove_file(&sorted);
+        fail(e);
+    }
+    let copied = File::open(&sorted)
+        .and_then(|mut file| io::copy(&mut file, &mut io::stdout().lock()))
+        .map(|_| ());
+    let _ = fs::remove_file(&sorted);
+    check_write(copied);
+}
+
+/// A new, empty file in the temporary directory for the sorted records.
+/// Its name can't be guessed, and it is created only if nothing has that
+/// name yet, so that no other user can have put a file or link there.
+fn scratch_file() -> Result<PathBuf, CsvError> {
+    loop {
+        let suffix = RandomState::new().build_hasher().finish();
+        let name = format!("csvtool-sort-{}-{:016x}.csv", process::id(), suffix);
+        let path = env::temp_dir().join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            // ERROR 1: temporary file could not be created.
+            Err(source) => return Err(CsvError::CouldNotOpenFile(CsvFileError { path, source })),
+        }
+    }
+}
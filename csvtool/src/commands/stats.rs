@@ -0,0 +1,40 @@
+use custom_errors::csvreader::{read_csv_with_options, CsvData, CsvReadOptions, Value};
+
+use super::{fail, json_output, path_str, print_json};
+use crate::cli::InputArgs;
+
+pub fn stats(args: &InputArgs) {
+    let options = args.apply(CsvReadOptions::new());
+    let path = path_str(&args.input);
+    let (data, _) = read_csv_with_options::<Value>(path, &options).unwrap_or_else(|e| fail(e));
+
+    let stats = data.describe();
+    if json_output() {
+        print_json(&stats);
+        return;
+    }
+
+    let number = |x: Option<f64>| x.map_or(String::new(), |x| x.to_string());
+    let header = [
+        "column", "type", "count", "missing", "min", "max", "mean", "distinct",
+    ];
+    let stats = CsvData {
+        header: header.map(String::from).to_vec(),
+        data: stats
+            .into_iter()
+            .map(|c| {
+                vec![
+                    c.name,
+                    c.column_type.to_string(),
+                    c.count.to_string(),
+                    c.missing.to_string(),
+                    number(c.min),
+                    number(c.max),
+                    c.mean.map_or(String::new(), |x| format!("{:.2}", x)),
+                    c.distinct.map_or(String::new(), |n| n.to_string()),
+                ]
+            })
+            .collect(),
+    };
+    print!("{}", stats);
+}
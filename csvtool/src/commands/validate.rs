@@ -0,0 +1,196 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+    sync::mpsc,
+    time::Duration,
+};
+
+use custom_errors::csvreader::{
+    read_csv_validated_with_options, read_csv_validated_with_schema, CsvConfig, CsvError,
+    CsvErrorReport, Value,
+};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde_json::json;
+
+use super::{fail, json_output, path_str, print_diagnostics, print_error, print_json};
+use crate::cli::{InputArgs, ValidateArgs};
+
+/// How long to wait for more changes before checking again, since editors
+/// often write a file in several steps.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Reads each file in collect-all mode and prints a summary line, e.g.
+/// `sales.csv: 120 rows checked, 2 errors, 1 warning`, or the report with
+/// `--format json`. Exits non-zero if there were errors.
+pub fn validate(args: &ValidateArgs) {
+    let config = load_config(args).unwrap_or_else(|e| fail(e));
+    let files = csv_files(&args.input.input);
+    if args.watch {
+        watch(args, config, files);
+    }
+    check_files(&files, &config, &args.input);
+}
+
+/// Checks and prints `files`, then exits with the code for the first
+/// problem found, if any.
+pub(super) fn check_files(files: &[PathBuf], config: &CsvConfig, input: &InputArgs) {
+    let mut category = None;
+    let mut reports = Vec::new();
+    for file in files {
+        let report = check(file, config, input).unwrap_or_else(|e| fail(e));
+        category = category.or(report.category());
+        reports.push(report);
+    }
+    if json_output() {
+        let results: Vec<_> = files.iter().zip(&reports).map(to_json).collect();
+        match input.input.is_dir() {
+            true => print_json(&results),
+            false => print_json(&results[0]),
+        }
+    } else {
+        for (file, report) in files.iter().zip(&reports) {
+            print_text(file, report, input, config);
+        }
+    }
+    if let Some(category) = category {
+        exit(category.exit_code());
+    }
+}
+
+/// Checks every file once, then again each time one of them or the schema
+/// changes. Runs until killed.
+fn watch(args: &ValidateArgs, mut config: CsvConfig, mut files: Vec<PathBuf>) -> ! {
+    let input = &args.input.input;
+    // Editors often replace a file rather than write to it, so the
+    // directory is watched rather than the file itself.
+    let dir = match input.is_dir() {
+        true => input.clone(),
+        false => input.parent().map_or(".".into(), Path::to_path_buf),
+    };
+    let schema = args.schema.as_ref().and_then(|s| fs::canonicalize(s).ok());
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).unwrap_or_else(|e| watch_failed(e));
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .unwrap_or_else(|e| watch_failed(e));
+    if let Some(schema) = &schema {
+        let parent = schema.parent().unwrap_or(Path::new("/"));
+        watcher
+            .watch(parent, RecursiveMode::NonRecursive)
+            .unwrap_or_else(|e| watch_failed(e));
+    }
+
+    run(&files, &config, &args.input);
+    let watched = |path: &Path| match input.is_dir() {
+        true => path.extension().is_some_and(|e| e == "csv"),
+        false => fs::canonicalize(input).is_ok_and(|input| input == path),
+    };
+    while let Ok(first) = rx.recv() {
+        let mut events = vec![first];
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            events.push(event);
+        }
+        let mut changed = BTreeSet::new();
+        for event in events.into_iter().flatten() {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                changed.extend(event.paths);
+            }
+        }
+
+        if schema.as_ref().is_some_and(|s| changed.contains(s)) {
+            match load_config(args) {
+                Ok(new) => config = new,
+                Err(e) => {
+                    print_error(&e);
+                    continue;
+                }
+            }
+            files = csv_files(input);
+            run(&files, &config, &args.input);
+            continue;
+        }
+        let changed: Vec<PathBuf> = changed
+            .into_iter()
+            .filter(|path| watched(path) && path.is_file())
+            .collect();
+        if !changed.is_empty() {
+            run(&changed, &config, &args.input);
+        }
+    }
+    exit(1);
+}
+
+fn watch_failed(e: notify::Error) -> ! {
+    eprintln!("error: could not watch for changes: {}", e);
+    exit(1);
+}
+
+/// Checks and prints `files`, without stopping on problems.
+fn run(files: &[PathBuf], config: &CsvConfig, input: &InputArgs) {
+    for file in files {
+        match check(file, config, input) {
+            Ok(report) if json_output() => print_json(&to_json((file, &report))),
+            Ok(report) => print_text(file, &report, input, config),
+            Err(e) => print_error(&e),
+        }
+    }
+}
+
+fn load_config(args: &ValidateArgs) -> Result<CsvConfig, CsvError> {
+    match &args.schema {
+        Some(schema) => CsvConfig::from_file(schema),
+        None => Ok(CsvConfig::default()),
+    }
+}
+
+/// The input itself, or the `.csv` files in it if it is a directory.
+pub(super) fn csv_files(input: &Path) -> Vec<PathBuf> {
+    if !input.is_dir() {
+        return vec![input.to_path_buf()];
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(input)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    files.retain(|path| path.is_file() && path.extension().is_some_and(|e| e == "csv"));
+    files.sort();
+    files
+}
+
+fn check(file: &Path, config: &CsvConfig, input: &InputArgs) -> Result<CsvErrorReport, CsvError> {
+    let options = input.apply(config.options.clone());
+    let path = path_str(file);
+    let report = match &config.schema {
+        Some(schema) => read_csv_validated_with_schema(path, schema, &options),
+        None => read_csv_validated_with_options::<Value>(path, &options),
+    };
+    report.map(|(_, report)| report)
+}
+
+fn to_json((file, report): (&PathBuf, &CsvErrorReport)) -> serde_json::Value {
+    json!({ "path": file, "ok": !report.has_errors(), "report": report })
+}
+
+fn print_text(file: &Path, report: &CsvErrorReport, input: &InputArgs, config: &CsvConfig) {
+    let path = path_str(file);
+    let delimiter = input.delimiter.unwrap_or(config.options.delimiter);
+    print_diagnostics(report, path, delimiter);
+    println!(
+        "{}: {} rows checked, {} {}, {} {}",
+        path,
+        report.records,
+        report.errors.len(),
+        plural(report.errors.len(), "error"),
+        report.warnings.len(),
+        plural(report.warnings.len(), "warning"),
+    );
+}
+
+fn plural(n: usize, word: &str) -> String {
+    match n {
+        1 => word.into(),
+        _ => format!("{}s", word),
+    }
+}
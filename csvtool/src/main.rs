@@ -0,0 +1,28 @@
+use clap::Parser;
+
+use crate::cli::{Cli, Command};
+
+mod cli;
+mod commands;
+
+fn main() {
+    let cli = Cli::parse();
+    commands::set_format(cli.format);
+    match cli.command {
+        Command::Read(args) => commands::read(&args),
+        Command::Validate(args) => commands::validate(&args),
+        Command::Convert(args) => commands::convert(&args),
+        Command::Stats(args) => commands::stats(&args),
+        Command::Select(args) => commands::select(&args),
+        Command::Join(args) => commands::join(&args),
+        Command::Sample(args) => commands::sample(&args),
+        Command::Sort(args) => commands::sort(&args),
+        Command::Dedupe(args) => commands::dedupe(&args),
+        Command::Query(args) => commands::query(&args),
+        Command::Diff(args) => commands::diff(&args),
+        Command::Schema(command) => commands::schema(&command),
+        Command::Print(args) => commands::print(&args),
+        Command::Head(args) => commands::head(&args),
+        Command::Tail(args) => commands::tail(&args),
+    }
+}
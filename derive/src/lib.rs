@@ -0,0 +1,128 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Path};
+
+/// Derives `CsvRecord` for a struct with named fields.
+///
+/// Field attributes:
+/// - `#[csv(rename = "px")]` reads the field from column `px`.
+/// - `#[csv(default)]` uses `Default::default()` if the column is missing or
+///   the cell is empty.
+/// - `#[csv(parse_with = "path::to::fn")]` parses with a
+///   `fn(&str) -> Result<T, E>` instead of `FromStr`.
+///
+/// Struct attribute `#[csv(crate = "path")]` sets the path of the module
+/// defining `CsvRecord`, `::custom_errors::csvreader` by default.
+#[proc_macro_derive(CsvRecord, attributes(csv))]
+pub fn derive_csv_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct FieldOpts {
+    rename: Option<String>,
+    default: bool,
+    parse_with: Option<Path>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut krate: Path = syn::parse_quote!(::custom_errors::csvreader);
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("csv")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                krate = meta.value()?.parse::<LitStr>()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown csv attribute"))
+            }
+        })?;
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CsvRecord can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "CsvRecord needs a struct with named fields",
+        ));
+    };
+
+    let mut columns = Vec::new();
+    let mut inits = Vec::new();
+    for (i, field) in fields.named.iter().enumerate() {
+        let ident = field.ident.as_ref().expect("named field");
+        let opts = field_opts(field)?;
+        let name = opts.rename.unwrap_or_else(|| ident.to_string());
+        let required = !opts.default;
+        columns.push(quote! {
+            #krate::RecordColumn { name: #name, required: #required }
+        });
+
+        let parse = match &opts.parse_with {
+            Some(path) => quote! { #path(text).map_err(|_| #i)? },
+            None => quote! { text.parse().map_err(|_| #i)? },
+        };
+        let init = if opts.default {
+            quote! {
+                match fields[#i] {
+                    Some(text) if !text.is_empty() => #parse,
+                    _ => ::std::default::Default::default(),
+                }
+            }
+        } else {
+            quote! {
+                match fields[#i] {
+                    Some(text) => #parse,
+                    None => return Err(#i),
+                }
+            }
+        };
+        inits.push(quote! { #ident: #init });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::CsvRecord for #ident #ty_generics #where_clause {
+            fn columns() -> &'static [#krate::RecordColumn] {
+                &[#(#columns),*]
+            }
+
+            fn from_fields(
+                fields: &[::std::option::Option<&str>],
+            ) -> ::std::result::Result<Self, usize> {
+                Ok(Self { #(#inits),* })
+            }
+        }
+    })
+}
+
+fn field_opts(field: &syn::Field) -> syn::Result<FieldOpts> {
+    let mut opts = FieldOpts {
+        rename: None,
+        default: false,
+        parse_with: None,
+    };
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("csv")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                opts.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                opts.default = true;
+            } else if meta.path.is_ident("parse_with") {
+                opts.parse_with = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+            } else {
+                return Err(meta.error("unknown csv attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(opts)
+}
@@ -1,101 +1,188 @@
-use std::{
-    error::Error,
-    fs::OpenOptions,
-    io::{self, BufRead, BufReader},
-    str::FromStr,
-};
-
-type Result<T> = std::result::Result<T, CsvError>;
-
-#[derive(Debug)]
-pub struct CsvData<T: Copy + Default + FromStr> {
-    pub header: Vec<String>,
-    pub data: Vec<Vec<T>>,
-}
-
-#[derive(Debug)]
-pub struct CsvLineLen {
-    pub line_num: usize,
-    pub num_entries: usize,
-}
-
-#[derive(Debug)]
-pub enum CsvError {
-    FileNonExistant,
-    CouldNotOpenFile(io::Error),
-    CouldNotParseLine(Box<dyn Error>),
-    FileIsEmpty,
-    CouldNotParseValue(String),
-    LineTooShort(CsvLineLen),
-    LineTooLong(CsvLineLen),
-}
-
-impl From<io::Error> for CsvError {
-    fn from(value: io::Error) -> Self {
-        Self::CouldNotOpenFile(value)
-    }
-}
-
-impl std::fmt::Display for CsvError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
-    }
-}
-
-impl Error for CsvError {}
+//! Reading CSV files into typed tables, with every problem found reported
+//! as a [`CsvError`].
+//!
+//! Reads start from [`CsvReaderBuilder`], which holds the options and
+//! reads a file, a reader or text with them; `read_csv` is the same with
+//! the defaults. `read_csv_with_options` and its variants, `stream_csv` and
+//! `read_csv_records` take [`CsvReadOptions`] directly, and [`CsvSchema`]
+//! describes what a file has to hold. `CsvWriter` writes
+//! tables back out, and with the `calamine` feature `read_xlsx` reads
+//! Excel worksheets the same way. Without the `std` feature only
+//! [`read_csv_from_bytes`], [`parse_csv`], [`parse_csv_validated`] and
+//! [`parse_csv_with_schema`] are left, reading text already in memory.
 
-pub fn read_csv<T: Copy + Default + FromStr>(filename: &str) -> Result<CsvData<T>> {
-    let lines = read_to_lines(filename)?;
-    // ERROR 4: file was empty
-    if lines.is_empty() {
-        return Err(CsvError::FileIsEmpty);
-    }
+#[cfg(feature = "arrow")]
+mod arrow;
+mod builder;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "std")]
+mod cast;
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "std")]
+mod dedup;
+mod diagnostic;
+#[cfg(feature = "std")]
+mod diff;
+mod error;
+#[cfg(feature = "serde")]
+mod export;
+#[cfg(feature = "std")]
+mod file;
+#[cfg(feature = "std")]
+mod fill;
+#[cfg(feature = "std")]
+mod fingerprint;
+mod flat;
+mod header;
+#[cfg(feature = "std")]
+mod infer;
+#[cfg(feature = "std")]
+mod intern;
+#[cfg(feature = "std")]
+mod join;
+#[cfg(feature = "serde")]
+mod json;
+mod messages;
+#[cfg(feature = "std")]
+mod multi;
+mod options;
+#[cfg(feature = "std")]
+mod output;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "std")]
+mod query;
+mod reader;
+#[cfg(feature = "std")]
+mod record;
+#[cfg(any(feature = "calamine", feature = "serde", feature = "sqlite"))]
+mod rows;
+#[cfg(feature = "std")]
+mod sample;
+mod schema;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "std")]
+mod sort;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod table;
+#[cfg(all(test, feature = "std"))]
+mod testutil;
+mod tokenizer;
+#[cfg(feature = "std")]
+mod upsert;
+mod value;
+#[cfg(feature = "std")]
+mod writer;
+#[cfg(feature = "calamine")]
+mod xlsx;
 
-    let header: Vec<String> = lines[0].split(",").map(|s| s.into()).collect();
-    let mut data: Vec<Vec<T>> = Vec::with_capacity(lines.len() - 1);
+#[cfg(feature = "arrow")]
+pub use arrow::read_feather;
+pub use builder::CsvReaderBuilder;
+#[cfg(feature = "cache")]
+pub use cache::read_csv_cached;
+#[cfg(feature = "config")]
+pub use config::CsvConfig;
+#[cfg(feature = "derive")]
+pub use custom_errors_derive::CsvRecord;
+#[cfg(feature = "std")]
+pub use dedup::Keep;
+pub use diagnostic::{render_diagnostic, render_diagnostic_with_delimiter};
+#[cfg(feature = "std")]
+pub use diff::{csv_diff, csv_diff_with_options, CellChange, CsvDiff, RowChange};
+#[cfg(feature = "std")]
+pub use error::{CsvConfigError, CsvFileError, CsvInFileError};
+pub use error::{
+    CsvConstraintError, CsvDuplicateKey, CsvError, CsvErrorReport, CsvFilterError, CsvHeaderDiff,
+    CsvMapError, CsvQueryError, CsvRecordLen, CsvRuleError, CsvSchemaError, CsvValuePos,
+    ErrorCategory, Severity, SkippedRecord,
+};
+#[cfg(feature = "serde")]
+pub use export::{write_json, write_jsonl};
+#[cfg(feature = "std")]
+pub use file::{
+    read_csv, read_csv_expect_header, read_csv_flat, read_csv_flat_with_options,
+    read_csv_validated, read_csv_validated_with_options, read_csv_with_options, stream_csv,
+    CsvRecords,
+};
+#[cfg(feature = "std")]
+pub use fill::Strategy;
+#[cfg(feature = "std")]
+pub use fingerprint::{csv_fingerprint, Fingerprint};
+pub use flat::FlatCsvData;
+#[cfg(feature = "std")]
+pub use infer::{infer_schema, infer_schema_with_options, infer_schema_with_ranges};
+#[cfg(feature = "std")]
+pub use intern::{read_csv_interned, read_csv_interned_validated, read_csv_interned_with_options};
+#[cfg(feature = "std")]
+pub use join::JoinKind;
+#[cfg(feature = "serde")]
+pub use json::{read_jsonl, read_jsonl_validated, read_jsonl_with_options};
+pub use messages::{Catalog, Message, MessageCatalog};
+#[cfg(feature = "std")]
+pub use multi::{read_csv_files, read_csv_files_validated};
+pub use options::{
+    ColumnSelector, CsvReadOptions, DuplicateHeaders, ErrorAction, OnError, RecordContext,
+    Strictness,
+};
+#[cfg(feature = "std")]
+pub use options::{ErrorHook, FooterHook};
+#[cfg(feature = "std")]
+pub use output::{write_csv, write_csv_with_options, write_file, WriteOptions};
+#[cfg(feature = "std")]
+pub use query::Filter;
+pub use reader::{parse_csv, parse_csv_flat, parse_csv_validated, read_csv_from_bytes};
+#[cfg(feature = "std")]
+pub use record::{
+    read_csv_records, read_csv_records_validated, read_csv_records_with_options, CsvRecord,
+    RecordColumn,
+};
+#[cfg(feature = "regex")]
+pub use regex::Regex;
+#[cfg(feature = "std")]
+pub use sample::{read_csv_sample, read_csv_sample_with_options};
+pub use schema::{
+    parse_csv_with_schema, ColumnOrder, ColumnSchema, Constraint, CsvSchema, Row, Rule,
+};
+#[cfg(feature = "std")]
+pub use schema::{
+    read_csv_validated_with_schema, read_csv_with_schema, read_csv_with_schema_options,
+};
+#[cfg(feature = "std")]
+pub use sort::{sort_csv_file, sort_csv_file_with_options, SortOptions};
+#[cfg(feature = "std")]
+pub use stats::ColumnStats;
+#[cfg(feature = "std")]
+pub use table::Table;
+#[cfg(feature = "std")]
+pub use upsert::{ConflictKind, UpsertConflict, UpsertReport};
+pub use value::{ColumnType, Value};
+#[cfg(feature = "std")]
+pub use writer::{roundtrip_check, write_markdown, CsvWriter};
+#[cfg(feature = "calamine")]
+pub use xlsx::{read_xlsx, read_xlsx_validated, read_xlsx_with_options};
 
-    for i in 1..lines.len() {
-        let entries: Vec<Result<T>> = lines[i]
-            .split(",")
-            .map(|e| {
-                let res = e.parse::<T>();
-                res.map_err(|_| CsvError::CouldNotParseValue(e.into()))
-            })
-            // ERROR 5: could not parse from string.
-            .collect();
+use alloc::{string::String, vec::Vec};
 
-        let entries: Vec<T> = entries.into_iter().collect::<Result<_>>()?;
-        // ERROR 6: line was too short.
-        if entries.len() == header.len() {
-            // ERROR 7 (hidden): line was too long.
-            data.push(entries);
-        } else if entries.len() < header.len() {
-            return Err(CsvError::LineTooShort(CsvLineLen {
-                line_num: i,
-                num_entries: entries.len(),
-            }));
-        } else {
-            return Err(CsvError::LineTooLong(CsvLineLen {
-                line_num: i,
-                num_entries: entries.len(),
-            }));
-        }
-    }
+#[cfg(feature = "std")]
+use file::read;
+#[cfg(feature = "std")]
+use reader::{line_breaks, parse_lines_flat, Engine, FromStrParser};
+use reader::{parse_lines, split_lines, FieldParser, Layout, Sink};
 
-    Ok(CsvData { header, data })
-}
+type Result<T> = core::result::Result<T, CsvError>;
 
-fn read_to_lines(filename: &str) -> Result<Vec<String>> {
-    let path = std::path::Path::new(filename);
-    // ERROR 1: file could be non-existant.
-    if !path.exists() {
-        return Err(CsvError::FileNonExistant);
-    }
-    let file = OpenOptions::new().read(true).open(path)?;
-    let lines: Vec<_> = BufReader::new(file).lines().collect();
-    // ERROR 3: line could not be parsed.
-    lines
-        .into_iter()
-        .map(|line| line.map_err(|e| CsvError::CouldNotParseLine(Box::new(e))))
-        .collect()
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct CsvData<T> {
+    pub header: Vec<String>,
+    pub data: Vec<Vec<T>>,
 }
@@ -0,0 +1,340 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
+
+use arrow_array::{
+    cast::AsArray,
+    types::{Float64Type, Int64Type},
+    Array, ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions,
+    StringArray,
+};
+use arrow_cast::{
+    cast,
+    display::{ArrayFormatter, FormatOptions},
+};
+use arrow_ipc::{reader::FileReader, writer::FileWriter};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+use super::{file::open_file, ColumnType, CsvData, CsvError, CsvFileError, Result, Value};
+
+/// Records per batch in files written by [`CsvData::write_feather`].
+const BATCH_ROWS: usize = 64 * 1024;
+
+/// Reads an Arrow IPC file, as written by [`CsvData::write_feather`] or
+/// another Arrow tool (Feather version 2). Booleans, integers and floats
+/// keep their type; anything else, such as dates, is read as its text.
+pub fn read_feather(path: &str) -> Result<CsvData<Value>> {
+    let file = open_file(path)?;
+    let reader = FileReader::try_new(BufReader::new(file), None).map_err(|e| invalid(path, e))?;
+    let header = field_names(&reader.schema());
+    let mut data = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|e| invalid(path, e))?;
+        data.extend(CsvData::from_record_batch(&batch)?.data);
+    }
+    Ok(CsvData { header, data })
+}
+
+impl CsvData<Value> {
+    /// Writes the table as an Arrow IPC file, which Arrow tools such as
+    /// pandas and polars open as Feather. Each column gets the narrowest
+    /// type all its values fit, as [`infer_schema`](super::infer_schema)
+    /// gives, `Text` for an all-null column.
+    pub fn write_feather(&self, path: &str) -> Result<()> {
+        let (schema, types) = self.arrow_schema();
+        let file_path = Path::new(path);
+        // ERROR 1: file could not be created.
+        let file = File::create(file_path).map_err(|e| CsvFileError::into_error(file_path, e))?;
+        let written = FileWriter::try_new(BufWriter::new(file), &schema).and_then(|mut writer| {
+            for rows in self.data.chunks(BATCH_ROWS) {
+                writer.write(&batch(&schema, &types, rows))?;
+            }
+            writer.finish()
+        });
+        // ERROR 20: file could not be written.
+        written.map_err(|e| {
+            CsvError::CouldNotWriteFile(CsvFileError {
+                path: file_path.to_path_buf(),
+                source: io::Error::other(e),
+            })
+        })
+    }
+
+    /// The table as one Arrow record batch, typed as by
+    /// [`write_feather`](CsvData::write_feather).
+    pub fn to_record_batch(&self) -> RecordBatch {
+        let (schema, types) = self.arrow_schema();
+        batch(&schema, &types, &self.data)
+    }
+
+    /// The records of `batch`, read as by [`read_feather`].
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self> {
+        let mut columns: Vec<_> = batch
+            .columns()
+            .iter()
+            .map(column_values)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            // ERROR 3: a column could not be read.
+            .map_err(|e| CsvError::CouldNotParseLine(Box::new(e)))?
+            .into_iter()
+            .map(Vec::into_iter)
+            .collect();
+        let data = (0..batch.num_rows())
+            .map(|_| {
+                columns
+                    .iter_mut()
+                    .map(|column| column.next().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        Ok(CsvData {
+            header: field_names(&batch.schema()),
+            data,
+        })
+    }
+
+    /// The schema to write the table with, and the type of each column.
+    fn arrow_schema(&self) -> (SchemaRef, Vec<ColumnType>) {
+        let mut types = Vec::with_capacity(self.header.len());
+        let fields: Vec<_> = self
+            .header
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let mut column_type: Option<ColumnType> = None;
+                let mut nullable = false;
+                for value in self.data.iter().map(|row| row.get(i)) {
+                    match value.and_then(Value::column_type) {
+                        Some(ty) => {
+                            column_type = Some(match column_type {
+                                Some(column_type) => column_type.widen(ty),
+                                None => ty,
+                            })
+                        }
+                        None => nullable = true,
+                    }
+                }
+                let column_type = column_type.unwrap_or(ColumnType::Text);
+                types.push(column_type);
+                let data_type = match column_type {
+                    ColumnType::Bool => DataType::Boolean,
+                    ColumnType::Int => DataType::Int64,
+                    ColumnType::Float => DataType::Float64,
+                    ColumnType::Text => DataType::Utf8,
+                };
+                Field::new(name, data_type, nullable)
+            })
+            .collect();
+        (Arc::new(Schema::new(fields)), types)
+    }
+}
+
+/// `rows` as a record batch with `schema`, whose columns have `types`.
+fn batch(schema: &SchemaRef, types: &[ColumnType], rows: &[Vec<Value>]) -> RecordBatch {
+    let columns = types
+        .iter()
+        .enumerate()
+        .map(|(i, column_type)| {
+            let values = rows.iter().map(|row| row.get(i).filter(|v| !v.is_null()));
+            let array: ArrayRef = match column_type {
+                ColumnType::Bool => Arc::new(
+                    values
+                        .map(|v| v.map(|v| matches!(v, Value::Bool(true))))
+                        .collect::<BooleanArray>(),
+                ),
+                ColumnType::Int => Arc::new(
+                    values
+                        .map(|v| match v {
+                            Some(Value::Int(i)) => Some(*i),
+                            _ => None,
+                        })
+                        .collect::<Int64Array>(),
+                ),
+                ColumnType::Float => Arc::new(
+                    values
+                        .map(|v| v.and_then(Value::as_f64))
+                        .collect::<Float64Array>(),
+                ),
+                ColumnType::Text => Arc::new(
+                    values
+                        .map(|v| v.map(Value::to_string))
+                        .collect::<StringArray>(),
+                ),
+            };
+            array
+        })
+        .collect();
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    RecordBatch::try_new_with_options(schema.clone(), columns, &options)
+        .expect("columns are built to the schema")
+}
+
+/// The values of a column, nulls as `Null`.
+fn column_values(array: &ArrayRef) -> std::result::Result<Vec<Value>, ArrowError> {
+    let values = |value: &dyn Fn(usize) -> Value| -> Vec<Value> {
+        (0..array.len())
+            .map(|i| match array.is_null(i) {
+                true => Value::Null,
+                false => value(i),
+            })
+            .collect()
+    };
+    let values = match array.data_type() {
+        DataType::Boolean => {
+            let array = array.as_boolean();
+            values(&|i| Value::Bool(array.value(i)))
+        }
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32 => {
+            let ints = cast(array, &DataType::Int64)?;
+            let ints = ints.as_primitive::<Int64Type>();
+            values(&|i| Value::Int(ints.value(i)))
+        }
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => {
+            let floats = cast(array, &DataType::Float64)?;
+            let floats = floats.as_primitive::<Float64Type>();
+            values(&|i| Value::Float(floats.value(i)))
+        }
+        data_type => {
+            // Numbers that may not fit an `i64` are parsed from their text.
+            let numeric = matches!(
+                data_type,
+                DataType::UInt64 | DataType::Decimal128(..) | DataType::Decimal256(..)
+            );
+            let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())?;
+            values(&|i| {
+                let text = formatter.value(i).to_string();
+                match numeric {
+                    true => Value::from_str(&text).unwrap_or_default(),
+                    false => Value::Text(text),
+                }
+            })
+        }
+    };
+    Ok(values)
+}
+
+fn field_names(schema: &Schema) -> Vec<String> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| field.name().clone())
+        .collect()
+}
+
+/// The error for a file that isn't a valid Arrow IPC file.
+fn invalid(path: &str, e: ArrowError) -> CsvError {
+    // ERROR 1: file is not an Arrow IPC file.
+    CsvError::CouldNotOpenFile(CsvFileError {
+        path: Path::new(path).to_path_buf(),
+        source: io::Error::new(io::ErrorKind::InvalidData, e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::UInt64Array;
+
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    fn table() -> CsvData<Value> {
+        let text = |s: &str| Value::Text(s.into());
+        CsvData {
+            header: ["b", "i", "f", "s", "none"].map(String::from).to_vec(),
+            data: vec![
+                vec![
+                    Value::Bool(true),
+                    Value::Int(1),
+                    Value::Int(2),
+                    text("a"),
+                    Value::Null,
+                ],
+                vec![
+                    Value::Null,
+                    Value::Int(-3),
+                    Value::Float(0.5),
+                    text("b,\"c\""),
+                    Value::Null,
+                ],
+                vec![Value::Bool(false), Value::Null],
+            ],
+        }
+    }
+
+    #[test]
+    fn columns_get_the_narrowest_type() {
+        let batch = table().to_record_batch();
+        let schema = batch.schema();
+        let types: Vec<_> = schema
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                DataType::Boolean,
+                DataType::Int64,
+                DataType::Float64,
+                DataType::Utf8,
+                DataType::Utf8
+            ]
+        );
+        let nullable: Vec<_> = schema.fields().iter().map(|f| f.is_nullable()).collect();
+        assert_eq!(nullable, [true, true, true, true, true]);
+        assert_eq!(batch.num_rows(), 3);
+    }
+
+    #[test]
+    fn record_batches_round_trip() {
+        let data = CsvData::from_record_batch(&table().to_record_batch()).unwrap();
+        assert_eq!(data.header, table().header);
+        let mut expected = table();
+        // Integers in a float column come back as floats, and short
+        // records as nulls.
+        expected.data[0][2] = Value::Float(2.0);
+        expected.data[2].resize(5, Value::Null);
+        assert_eq!(data.data, expected.data);
+    }
+
+    #[test]
+    fn feather_files_round_trip() {
+        let file = TempFile::empty("table.feather");
+        table().write_feather(file.path()).unwrap();
+        let data = read_feather(file.path()).unwrap();
+        let from_batch = CsvData::from_record_batch(&table().to_record_batch()).unwrap();
+        assert_eq!(data.data, from_batch.data);
+    }
+
+    #[test]
+    fn unsigned_integers_are_read_by_value() {
+        let schema = Schema::new(vec![Field::new("n", DataType::UInt64, true)]);
+        let column: ArrayRef = Arc::new(UInt64Array::from(vec![Some(u64::MAX), None, Some(7)]));
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![column]).unwrap();
+        let data = CsvData::from_record_batch(&batch).unwrap();
+        assert_eq!(data.data[1..], [vec![Value::Null], vec![Value::Int(7)]]);
+        assert_eq!(
+            data.data[0],
+            [Value::from_str("18446744073709551615").unwrap()]
+        );
+    }
+
+    #[test]
+    fn other_files_are_invalid() {
+        let file = TempFile::new("not.feather", "a,b\n1,2\n");
+        assert!(matches!(
+            read_feather(file.path()),
+            Err(CsvError::CouldNotOpenFile(e)) if e.source.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+}
@@ -0,0 +1,207 @@
+use alloc::vec::Vec;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::Read;
+
+#[cfg(feature = "std")]
+use super::{file, CsvError, ErrorAction, RecordContext};
+use super::{
+    parse_lines, reader::FromStrParser, split_lines, ColumnSelector, CsvData, CsvErrorReport,
+    CsvReadOptions, DuplicateHeaders, OnError, Result, Sink, Strictness,
+};
+
+/// Configures a read and runs it: the setters are those of
+/// [`CsvReadOptions`], and [`from_path`](CsvReaderBuilder::from_path),
+/// [`from_reader`](CsvReaderBuilder::from_reader) and
+/// [`from_str`](CsvReaderBuilder::from_str) read with them.
+///
+/// ```no_run
+/// # use custom_errors::csvreader::{CsvReaderBuilder, OnError, Value};
+/// let (data, report) = CsvReaderBuilder::new()
+///     .delimiter(';')
+///     .null_values(&["NA"])
+///     .on_error(OnError::Skip)
+///     .from_path::<Value>("trades.csv")?;
+/// # Ok::<(), custom_errors::csvreader::CsvError>(())
+/// ```
+///
+/// [`read_csv`](super::read_csv) is the same read with the defaults.
+#[derive(Debug, Clone, Default)]
+pub struct CsvReaderBuilder {
+    options: CsvReadOptions,
+    collect_errors: bool,
+}
+
+impl CsvReaderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces every option at once, such as with those of a
+    /// `CsvConfig`, with the `config` feature.
+    pub fn options(mut self, options: CsvReadOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The options set so far.
+    pub fn read_options(&self) -> &CsvReadOptions {
+        &self.options
+    }
+
+    /// Reads the whole input and reports every problem found, as
+    /// [`read_csv_validated`](super::read_csv_validated) does, instead of
+    /// stopping at the first error `on_error` doesn't skip.
+    pub fn collect_all(mut self, collect: bool) -> Self {
+        self.collect_errors = collect;
+        self
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.options = self.options.delimiter(delimiter);
+        self
+    }
+
+    pub fn quote(mut self, quote: Option<char>) -> Self {
+        self.options = self.options.quote(quote);
+        self
+    }
+
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.options = self.options.has_header(has_header);
+        self
+    }
+
+    /// See [`CsvReadOptions::header_rows`].
+    pub fn header_rows(mut self, rows: usize) -> Self {
+        self.options = self.options.header_rows(rows);
+        self
+    }
+
+    pub fn header_separator(mut self, separator: &str) -> Self {
+        self.options = self.options.header_separator(separator);
+        self
+    }
+
+    /// See [`CsvReadOptions::rename`].
+    pub fn rename(mut self, names: &[(&str, &str)]) -> Self {
+        self.options = self.options.rename(names);
+        self
+    }
+
+    pub fn null_values(mut self, values: &[&str]) -> Self {
+        self.options = self.options.null_values(values);
+        self
+    }
+
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.options = self.options.strictness(strictness);
+        self
+    }
+
+    pub fn on_error(mut self, on_error: OnError) -> Self {
+        self.options = self.options.on_error(on_error);
+        self
+    }
+
+    pub fn pad_short_lines(mut self, pad: bool) -> Self {
+        self.options = self.options.pad_short_lines(pad);
+        self
+    }
+
+    pub fn truncate_long_lines(mut self, truncate: bool) -> Self {
+        self.options = self.options.truncate_long_lines(truncate);
+        self
+    }
+
+    pub fn max_errors(mut self, max_errors: usize) -> Self {
+        self.options = self.options.max_errors(max_errors);
+        self
+    }
+
+    pub fn expect_header(mut self, expected: &[&str]) -> Self {
+        self.options = self.options.expect_header(expected);
+        self
+    }
+
+    pub fn duplicate_headers(mut self, policy: DuplicateHeaders) -> Self {
+        self.options = self.options.duplicate_headers(policy);
+        self
+    }
+
+    pub fn select(mut self, columns: Vec<ColumnSelector>) -> Self {
+        self.options = self.options.select(columns);
+        self
+    }
+
+    pub fn skip_footer(mut self, records: usize) -> Self {
+        self.options = self.options.skip_footer(records);
+        self
+    }
+
+    /// See [`CsvReadOptions::footer_when`].
+    #[cfg(feature = "std")]
+    pub fn footer_when(mut self, f: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.options = self.options.footer_when(f);
+        self
+    }
+
+    /// See [`CsvReadOptions::on_problem`].
+    #[cfg(feature = "std")]
+    pub fn on_problem(
+        mut self,
+        f: impl FnMut(&CsvError, &RecordContext) -> ErrorAction + Send + 'static,
+    ) -> Self {
+        self.options = self.options.on_problem(f);
+        self
+    }
+
+    /// Reads the file at `path`, see
+    /// [`read_csv_with_options`](super::read_csv_with_options).
+    #[cfg(feature = "std")]
+    pub fn from_path<T: Default + FromStr>(
+        &self,
+        path: &str,
+    ) -> Result<(CsvData<T>, CsvErrorReport)> {
+        file::read(path, &self.options, self.collect_errors, &mut FromStrParser)
+    }
+
+    /// Reads everything `reader` gives, such as standard input or a
+    /// decompressed stream.
+    #[cfg(feature = "std")]
+    pub fn from_reader<T: Default + FromStr>(
+        &self,
+        mut reader: impl Read,
+    ) -> Result<(CsvData<T>, CsvErrorReport)> {
+        let mut bytes = Vec::new();
+        // ERROR 3: input could not be read.
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| CsvError::CouldNotParseLine(Box::new(e)))?;
+        self.from_bytes(&bytes)
+    }
+
+    /// Reads CSV text that is already in memory.
+    pub fn from_str<T: Default + FromStr>(
+        &self,
+        text: &str,
+    ) -> Result<(CsvData<T>, CsvErrorReport)> {
+        self.from_bytes(text.as_bytes())
+    }
+
+    /// Reads CSV text that is already in memory, checking that it is UTF-8,
+    /// see [`parse_csv`](super::parse_csv).
+    pub fn from_bytes<T: Default + FromStr>(
+        &self,
+        input: &[u8],
+    ) -> Result<(CsvData<T>, CsvErrorReport)> {
+        let sink = Sink::new(&self.options, self.collect_errors);
+        parse_lines(&split_lines(input, &self.options), sink, &mut FromStrParser)
+    }
+}
+
+impl From<CsvReadOptions> for CsvReaderBuilder {
+    fn from(options: CsvReadOptions) -> Self {
+        Self::new().options(options)
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use bincode::{config, Decode, Encode};
+
+use super::{
+    csv_fingerprint, fingerprint::metadata, read_csv_with_options, CsvData, CsvError, CsvFileError,
+    CsvReadOptions, Fingerprint, Result,
+};
+
+/// Changes whenever the layout of a cache file does, so that old caches are
+/// rebuilt rather than misread.
+const CACHE_VERSION: u32 = 3;
+
+/// Like [`read_csv_with_options`], but keeps the table in `cache` and reads
+/// it from there as long as `filename` is unchanged, skipping parsing. The
+/// cache is also rebuilt when read with other options; of the hooks, only
+/// whether one is set counts.
+///
+/// Only the table is kept: on a warm start there is no report to return.
+pub fn read_csv_cached<T>(
+    filename: &str,
+    cache: &str,
+    options: &CsvReadOptions,
+) -> Result<CsvData<T>>
+where
+    T: Default + FromStr + Encode + Decode<()>,
+{
+    let read_with = format!("{:?}", options);
+    let (data, fingerprint) = CsvData::lookup(cache, filename, Some(&read_with))?;
+    if let Some(data) = data {
+        // The size or time changed but not the contents: the key is
+        // rewritten so that the next start doesn't hash the file again.
+        if let Some(source) = fingerprint {
+            data.save_cache_with_key(cache, source, read_with)?;
+        }
+        return Ok(data);
+    }
+    // Taken before parsing, so that a change made during the parse makes
+    // the cache stale instead of being stored as what it was built from.
+    let source = match fingerprint {
+        Some(source) => source,
+        None => csv_fingerprint(filename)?,
+    };
+    let (data, _) = read_csv_with_options(filename, options)?;
+    data.save_cache_with_key(cache, source, read_with)?;
+    Ok(data)
+}
+
+/// What a cache was built from, to tell when it is stale.
+#[derive(Encode, Decode, PartialEq)]
+struct CacheKey {
+    version: u32,
+    /// The type of the values, since a cache read back as another type
+    /// would give garbage.
+    value_type: String,
+    /// The options the table was read with, as `Debug` shows them, or
+    /// empty if they aren't known.
+    options: String,
+    source: Fingerprint,
+}
+
+impl<T: Encode> CsvData<T> {
+    /// Stores the table in `cache`, to be read back by
+    /// [`load_cache`](CsvData::load_cache) while `source` is unchanged.
+    pub fn save_cache(&self, cache: &str, source: &str) -> Result<()> {
+        self.save_cache_with_key(cache, csv_fingerprint(source)?, String::new())
+    }
+
+    /// Stores the table in `cache` as read from the file with `source` with
+    /// `options`.
+    fn save_cache_with_key(&self, cache: &str, source: Fingerprint, options: String) -> Result<()> {
+        let key = CacheKey {
+            version: CACHE_VERSION,
+            value_type: std::any::type_name::<T>().into(),
+            options,
+            source,
+        };
+        let path = Path::new(cache);
+        // ERROR 1: cache could not be created.
+        let file = File::create(path).map_err(|e| CsvFileError::into_error(path, e))?;
+        let mut out = BufWriter::new(file);
+        let written = bincode::encode_into_std_write(&key, &mut out, config::standard())
+            .and_then(|_| bincode::encode_into_std_write(self, &mut out, config::standard()))
+            .map_err(io::Error::other)
+            .and_then(|_| out.flush());
+        // ERROR 20: cache could not be written.
+        written.map_err(|source| {
+            CsvError::CouldNotWriteFile(CsvFileError {
+                path: path.to_path_buf(),
+                source,
+            })
+        })
+    }
+}
+
+impl<T: Decode<()>> CsvData<T> {
+    /// The table stored in `cache` by [`save_cache`](CsvData::save_cache),
+    /// or `None` if there is none, or `source` has changed since. A cache
+    /// that can't be read is treated as stale.
+    ///
+    /// A source with the same size and modification time is taken to be
+    /// unchanged. Otherwise its contents are hashed, so that a file that was
+    /// only touched keeps its cache. The options the table was read with
+    /// are not checked.
+    pub fn load_cache(cache: &str, source: &str) -> Result<Option<Self>> {
+        Self::lookup(cache, source, None).map(|(data, _)| data)
+    }
+
+    /// [`load_cache`](CsvData::load_cache), along with the fingerprint of
+    /// `source` if it had to be taken: for a table, contents that match the
+    /// key under a new size or time; without one, to build the cache with.
+    /// With `options`, a cache read with others is stale.
+    fn lookup(
+        cache: &str,
+        source: &str,
+        options: Option<&str>,
+    ) -> Result<(Option<Self>, Option<Fingerprint>)> {
+        let Ok(file) = File::open(cache) else {
+            return Ok((None, None));
+        };
+        let mut input = BufReader::new(file);
+        let Ok(key) =
+            bincode::decode_from_std_read::<CacheKey, _, _>(&mut input, config::standard())
+        else {
+            return Ok((None, None));
+        };
+        if key.version != CACHE_VERSION
+            || key.value_type != std::any::type_name::<T>()
+            || options.is_some_and(|options| key.options != options)
+        {
+            return Ok((None, None));
+        }
+        // Only a source whose size or time changed is hashed.
+        let (len, modified) = metadata(source)?;
+        let fingerprint =
+            match len == key.source.len && modified.is_some() && modified == key.source.modified {
+                true => None,
+                false => Some(csv_fingerprint(source)?),
+            };
+        if let Some(fingerprint) = &fingerprint {
+            if !key.source.same_contents(fingerprint) {
+                return Ok((None, Some(fingerprint.clone())));
+            }
+        }
+        let data = bincode::decode_from_std_read(&mut input, config::standard()).ok();
+        Ok((data, fingerprint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        time::{Duration, SystemTime},
+    };
+
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    /// A table the file doesn't hold, so that reading it back shows the
+    /// cache was used.
+    fn planted() -> CsvData<String> {
+        CsvData {
+            header: vec!["from".into()],
+            data: vec![vec!["cache".into()]],
+        }
+    }
+
+    /// `planted` stored as the cache of `source` read with `options`.
+    fn plant(cache: &TempFile, source: &TempFile, options: &CsvReadOptions) {
+        let fingerprint = csv_fingerprint(source.path()).unwrap();
+        planted()
+            .save_cache_with_key(cache.path(), fingerprint, format!("{:?}", options))
+            .unwrap();
+    }
+
+    fn read(source: &TempFile, cache: &TempFile, options: &CsvReadOptions) -> CsvData<String> {
+        read_csv_cached(source.path(), cache.path(), options).unwrap()
+    }
+
+    #[test]
+    fn an_unchanged_file_is_read_from_the_cache() {
+        let source = TempFile::new("cached.csv", "a,b\n1,2\n");
+        let cache = TempFile::empty("cached.cache");
+        let options = CsvReadOptions::default();
+        let data = read(&source, &cache, &options);
+        assert_eq!(data.data, [["1", "2"]]);
+        assert_eq!(read(&source, &cache, &options).data, data.data);
+
+        plant(&cache, &source, &options);
+        assert_eq!(read(&source, &cache, &options).data, planted().data);
+    }
+
+    #[test]
+    fn a_touched_file_keeps_its_cache() {
+        let source = TempFile::new("touched.csv", "a,b\n1,2\n");
+        let cache = TempFile::empty("touched.cache");
+        let options = CsvReadOptions::default();
+        plant(&cache, &source, &options);
+        let later = SystemTime::now() + Duration::from_secs(60);
+        let file = File::options().write(true).open(source.path()).unwrap();
+        file.set_modified(later).unwrap();
+        assert_eq!(read(&source, &cache, &options).data, planted().data);
+        // The key was brought up to date, so the file isn't hashed again.
+        let read_with = format!("{:?}", options);
+        let (data, fingerprint) =
+            CsvData::<String>::lookup(cache.path(), source.path(), Some(&read_with)).unwrap();
+        assert!(data.is_some());
+        assert!(fingerprint.is_none());
+    }
+
+    #[test]
+    fn changed_contents_are_read_again() {
+        let source = TempFile::new("changed.csv", "a,b\n1,2\n");
+        let cache = TempFile::empty("changed.cache");
+        let options = CsvReadOptions::default();
+        plant(&cache, &source, &options);
+        fs::write(source.path(), "a,b\n3,4\n5,6\n").unwrap();
+        assert_eq!(
+            read(&source, &cache, &options).data,
+            [["3", "4"], ["5", "6"]]
+        );
+        let cached = CsvData::<String>::load_cache(cache.path(), source.path()).unwrap();
+        assert_eq!(cached.unwrap().data, [["3", "4"], ["5", "6"]]);
+    }
+
+    #[test]
+    fn other_options_are_read_again() {
+        let source = TempFile::new("options.csv", "a;b\n1;2\n");
+        let cache = TempFile::empty("options.cache");
+        let commas = CsvReadOptions::default();
+        assert_eq!(read(&source, &cache, &commas).header, ["a;b"]);
+        let semicolons = CsvReadOptions::new().delimiter(';');
+        let data = read(&source, &cache, &semicolons);
+        assert_eq!(data.header, ["a", "b"]);
+        assert_eq!(data.data, [["1", "2"]]);
+        let no_header = semicolons.clone().has_header(false);
+        assert_eq!(read(&source, &cache, &no_header).data.len(), 2);
+    }
+
+    #[test]
+    fn another_value_type_is_read_again() {
+        let source = TempFile::new("typed.csv", "a,b\n1,2\n");
+        let cache = TempFile::empty("typed.cache");
+        let options = CsvReadOptions::default();
+        plant(&cache, &source, &options);
+        let data: CsvData<i64> = read_csv_cached(source.path(), cache.path(), &options).unwrap();
+        assert_eq!(data.data, [[1, 2]]);
+        assert!(CsvData::<String>::load_cache(cache.path(), source.path())
+            .unwrap()
+            .is_none());
+    }
+}
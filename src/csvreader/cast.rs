@@ -0,0 +1,143 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{CsvData, CsvError, CsvErrorReport, CsvValuePos, FieldParser, FromStrParser};
+
+impl<T: Display> CsvData<T> {
+    /// The values of `column`, each converted to `U` from its text, such as
+    /// the quantities of a table read as strings. Fails with every value
+    /// that doesn't convert or is missing from a short record, each
+    /// reported as the reader would, with a line number counting the header
+    /// as line 1.
+    pub fn cast_column<U: FromStr>(&self, column: &str) -> Result<Vec<U>, CsvErrorReport> {
+        let Some(i) = self.header.iter().position(|name| name == column) else {
+            return Err(CsvErrorReport {
+                errors: vec![CsvError::UnknownColumn(column.into())],
+                ..CsvErrorReport::default()
+            });
+        };
+        let mut report = CsvErrorReport::default();
+        let values = self
+            .data
+            .iter()
+            .enumerate()
+            .filter_map(|(row, record)| match record.get(i) {
+                Some(value) => cast(value, row, i, &mut report),
+                None => {
+                    // WARNING 4: a record too short to have the column is
+                    // missing the cell, reported as an empty one.
+                    report.errors.push(CsvError::EmptyValue(CsvValuePos {
+                        line_num: row + 2,
+                        column: i,
+                        value: String::new(),
+                    }));
+                    None
+                }
+            })
+            .collect();
+        finish(values, report, self.data.len())
+    }
+
+    /// The whole table with every value converted to `U`, see
+    /// [`cast_column`](CsvData::cast_column).
+    pub fn try_cast<U: FromStr>(&self) -> Result<CsvData<U>, CsvErrorReport> {
+        let mut report = CsvErrorReport::default();
+        let data = self
+            .data
+            .iter()
+            .enumerate()
+            .filter_map(|(row, record)| {
+                let before = report.errors.len();
+                let values: Vec<U> = record
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, value)| cast(value, row, i, &mut report))
+                    .collect();
+                (report.errors.len() == before).then_some(values)
+            })
+            .collect();
+        let data = finish(data, report, self.data.len())?;
+        Ok(CsvData {
+            header: self.header.clone(),
+            data,
+        })
+    }
+}
+
+/// `value` as a `U`, or `None` after adding the problem to `report`.
+fn cast<U: FromStr>(
+    value: &impl Display,
+    row: usize,
+    column: usize,
+    report: &mut CsvErrorReport,
+) -> Option<U> {
+    // Line 1 is the header.
+    let line_num = row + 2;
+    FieldParser::<U>::parse(&FromStrParser, &value.to_string(), line_num, column)
+        .map_err(|e| report.errors.push(e))
+        .ok()
+}
+
+/// `values`, unless `report` holds errors.
+pub(super) fn finish<V>(
+    values: V,
+    mut report: CsvErrorReport,
+    records: usize,
+) -> Result<V, CsvErrorReport> {
+    report.records = records;
+    match report.has_errors() {
+        true => Err(report),
+        false => Ok(values),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> CsvData<String> {
+        let rows: [&[&str]; 4] = [&["1", "a"], &["x", "b"], &["3"], &["4", "d"]];
+        CsvData {
+            header: vec!["n".into(), "s".into()],
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn casts_a_column() {
+        let mut data = table();
+        data.data.remove(1);
+        assert_eq!(data.cast_column::<i64>("n").unwrap(), [1, 3, 4]);
+    }
+
+    #[test]
+    fn reports_every_bad_cell() {
+        let report = table().cast_column::<i64>("n").unwrap_err();
+        assert_eq!(report.records, 4);
+        let errors: Vec<_> = report
+            .errors
+            .iter()
+            .map(|e| (e.line_num(), e.column()))
+            .collect();
+        assert_eq!(errors, [(Some(3), Some(0))]);
+        assert!(matches!(report.errors[0], CsvError::CouldNotParseValue(_)));
+    }
+
+    #[test]
+    fn missing_cells_are_errors() {
+        let report = table().cast_column::<String>("s").unwrap_err();
+        assert_eq!(report.errors.len(), 1);
+        assert!(matches!(
+            &report.errors[0],
+            CsvError::EmptyValue(pos) if pos.line_num == 4 && pos.column == 1
+        ));
+    }
+
+    #[test]
+    fn unknown_column() {
+        let report = table().cast_column::<i64>("m").unwrap_err();
+        assert!(matches!(&report.errors[..], [CsvError::UnknownColumn(c)] if c == "m"));
+    }
+}
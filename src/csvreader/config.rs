@@ -0,0 +1,354 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ColumnOrder, ColumnSchema, ColumnSelector, ColumnType, Constraint, CsvConfigError, CsvError,
+    CsvFileError, CsvReadOptions, CsvSchema, DuplicateHeaders, OnError, Result, Strictness, Value,
+};
+
+/// Reader options and an optional schema, loaded from a TOML (`.toml`) or
+/// YAML (`.yaml`, `.yml`) file:
+///
+/// ```toml
+/// delimiter = ";"
+/// null_values = ["NA", "-"]
+/// strictness = "warn"
+/// on_error = "skip"
+///
+/// [rename]
+/// "Cust ID" = "customer_id"
+///
+/// [schema]
+/// order = "any"
+/// unique = [["id"]]
+///
+/// [[schema.columns]]
+/// name = "id"
+/// type = "int"
+/// min = 1
+///
+/// [[schema.columns]]
+/// name = "status"
+/// type = "text"
+/// default = "new"
+/// pattern = "^[a-z]+$"
+/// ```
+///
+/// Every key is optional. Row rules can only be added in code.
+#[derive(Debug, Clone, Default)]
+pub struct CsvConfig {
+    pub options: CsvReadOptions,
+    pub schema: Option<CsvSchema>,
+}
+
+impl CsvConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let invalid = |reason: String| {
+            CsvError::InvalidConfig(CsvConfigError {
+                path: path.to_path_buf(),
+                reason,
+            })
+        };
+        let text = fs::read_to_string(path).map_err(|e| CsvFileError::into_error(path, e))?;
+        let file: ConfigFile = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str(&text).map_err(|e| invalid(e.message().into()))?,
+            Some("yaml" | "yml") => {
+                serde_yaml::from_str(&text).map_err(|e| invalid(e.to_string()))?
+            }
+            _ => return Err(invalid("expected a .toml, .yaml or .yml file".into())),
+        };
+        file.into_config().map_err(invalid)
+    }
+}
+
+impl CsvSchema {
+    /// The schema as a [`CsvConfig`] file with only a `[schema]` section.
+    /// Rules can't be written out and are left out.
+    pub fn to_toml(&self) -> String {
+        // Every field has a TOML representation, so this can't fail.
+        toml::to_string(&SchemaFile::from(self)).unwrap_or_default()
+    }
+
+    /// Like [`to_toml`](Self::to_toml), as YAML.
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(&SchemaFile::from(self)).unwrap_or_default()
+    }
+}
+
+impl CsvReadOptions {
+    /// The options part of a [`CsvConfig`] file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        CsvConfig::from_file(path).map(|config| config.options)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    delimiter: char,
+    /// A single character, or `""` for none.
+    quote: String,
+    has_header: bool,
+    header_rows: usize,
+    header_separator: String,
+    /// Maps names in the file to the names to use.
+    rename: BTreeMap<String, String>,
+    null_values: Vec<String>,
+    strictness: Strictness,
+    on_error: OnError,
+    pad_short_lines: bool,
+    truncate_long_lines: bool,
+    max_errors: Option<usize>,
+    expected_header: Option<Vec<String>>,
+    duplicate_headers: DuplicateHeaders,
+    /// Column selectors, e.g. `["id", "3-5"]`.
+    columns: Option<Vec<String>>,
+    skip_footer: usize,
+    schema: Option<SchemaConfig>,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        let options = CsvReadOptions::default();
+        Self {
+            delimiter: options.delimiter,
+            quote: options.quote.map(String::from).unwrap_or_default(),
+            has_header: options.has_header,
+            header_rows: options.header_rows,
+            header_separator: options.header_separator,
+            rename: options.rename.into_iter().collect(),
+            null_values: options.null_values,
+            strictness: options.strictness,
+            on_error: options.on_error,
+            pad_short_lines: options.pad_short_lines,
+            truncate_long_lines: options.truncate_long_lines,
+            max_errors: options.max_errors,
+            expected_header: options.expected_header,
+            duplicate_headers: options.duplicate_headers,
+            columns: None,
+            skip_footer: options.skip_footer,
+            schema: None,
+        }
+    }
+}
+
+/// Just the schema, for writing one out.
+#[derive(Serialize)]
+struct SchemaFile {
+    schema: SchemaConfig,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaConfig {
+    #[serde(default)]
+    order: ColumnOrder,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    unique: Vec<Vec<String>>,
+    columns: Vec<ColumnConfig>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct ColumnConfig {
+    name: String,
+    #[serde(rename = "type")]
+    column_type: ColumnType,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    nullable: bool,
+    /// Implied `false` by a `default`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<Scalar>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_length: Option<usize>,
+}
+
+/// A default value, as written in the file.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum Scalar {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl ConfigFile {
+    fn into_config(self) -> std::result::Result<CsvConfig, String> {
+        let mut quote = self.quote.chars();
+        let options = CsvReadOptions {
+            delimiter: self.delimiter,
+            quote: match (quote.next(), quote.next()) {
+                (quote, None) => quote,
+                _ => return Err("quote must be one character, or empty for none".into()),
+            },
+            has_header: self.has_header,
+            header_rows: self.header_rows,
+            header_separator: self.header_separator,
+            rename: self.rename.into_iter().collect(),
+            null_values: self.null_values,
+            strictness: self.strictness,
+            on_error: self.on_error,
+            pad_short_lines: self.pad_short_lines,
+            truncate_long_lines: self.truncate_long_lines,
+            max_errors: self.max_errors,
+            expected_header: self.expected_header,
+            duplicate_headers: self.duplicate_headers,
+            columns: self
+                .columns
+                .map(|columns| {
+                    columns
+                        .iter()
+                        .map(|c| c.parse::<ColumnSelector>())
+                        .collect()
+                })
+                .transpose()?,
+            skip_footer: self.skip_footer,
+            footer: None,
+            error_hook: None,
+        };
+        let schema = self.schema.map(SchemaConfig::into_schema).transpose()?;
+        Ok(CsvConfig { options, schema })
+    }
+}
+
+impl SchemaConfig {
+    fn into_schema(self) -> std::result::Result<CsvSchema, String> {
+        let mut schema = CsvSchema::new().order(self.order);
+        for column in self.columns {
+            schema = schema.column(column.into_column()?);
+        }
+        schema.unique_keys = self.unique;
+        Ok(schema)
+    }
+}
+
+impl ColumnConfig {
+    fn into_column(self) -> std::result::Result<ColumnSchema, String> {
+        let mut column = ColumnSchema::new(&self.name, self.column_type);
+        if self.nullable {
+            column = column.nullable();
+        }
+        let default = self.default.map(|default| match default {
+            Scalar::Bool(b) => Value::Bool(b),
+            Scalar::Int(i) => Value::Int(i),
+            Scalar::Float(x) => Value::Float(x),
+            Scalar::Text(s) => Value::Text(s),
+        });
+        match (self.required, default) {
+            (Some(true), Some(_)) => {
+                return Err(format!(
+                    "column {}: a default needs required = false",
+                    self.name
+                ));
+            }
+            (_, Some(default)) => column = column.or_default(default),
+            (Some(false), None) => column = column.optional(),
+            _ => {}
+        }
+        if let Some(min) = self.min {
+            column = column.min(min);
+        }
+        if let Some(max) = self.max {
+            column = column.max(max);
+        }
+        #[cfg(feature = "regex")]
+        if let Some(pattern) = self.pattern {
+            let re = Regex::new(&pattern).map_err(|e| format!("column {}: {}", self.name, e))?;
+            column = column.pattern(re);
+        }
+        #[cfg(not(feature = "regex"))]
+        if self.pattern.is_some() {
+            return Err(format!(
+                "column {}: pattern needs the `regex` feature",
+                self.name
+            ));
+        }
+        if let Some(len) = self.max_length {
+            column = column.max_length(len);
+        }
+        Ok(column)
+    }
+}
+
+impl From<&CsvSchema> for SchemaFile {
+    fn from(schema: &CsvSchema) -> Self {
+        let columns = schema.columns.iter().map(ColumnConfig::from).collect();
+        Self {
+            schema: SchemaConfig {
+                order: schema.order,
+                unique: schema.unique_keys.clone(),
+                columns,
+            },
+        }
+    }
+}
+
+impl From<&ColumnSchema> for ColumnConfig {
+    fn from(column: &ColumnSchema) -> Self {
+        let default = match &column.default {
+            Value::Null => None,
+            Value::Bool(b) => Some(Scalar::Bool(*b)),
+            Value::Int(i) => Some(Scalar::Int(*i)),
+            Value::Float(x) => Some(Scalar::Float(*x)),
+            Value::Text(s) => Some(Scalar::Text(s.clone())),
+        };
+        let mut config = Self {
+            name: column.name.clone(),
+            column_type: column.column_type,
+            nullable: column.nullable,
+            required: (!column.required && default.is_none()).then_some(false),
+            default,
+            min: None,
+            max: None,
+            pattern: None,
+            max_length: None,
+        };
+        // A file holds one bound of each kind, so the tightest is kept.
+        // Patterns can't be combined into one; the last is kept.
+        for constraint in &column.constraints {
+            match *constraint {
+                Constraint::Min(min) => config.min = Some(config.min.map_or(min, |m| m.max(min))),
+                Constraint::Max(max) => config.max = Some(config.max.map_or(max, |m| m.min(max))),
+                #[cfg(feature = "regex")]
+                Constraint::Pattern(ref re) => config.pattern = Some(re.as_str().into()),
+                Constraint::MaxLength(len) => {
+                    config.max_length = Some(config.max_length.map_or(len, |m| m.min(len)))
+                }
+            }
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exported_bounds_are_the_tightest() {
+        let column = ColumnSchema::new("x", ColumnType::Int)
+            .min(1.0)
+            .min(5.0)
+            .max(7.0)
+            .max(10.0)
+            .max_length(4)
+            .max_length(8);
+        let toml = CsvSchema::new().column(column).to_toml();
+        assert!(toml.contains("min = 5.0"), "{}", toml);
+        assert!(toml.contains("max = 7.0"), "{}", toml);
+        assert!(toml.contains("max_length = 4"), "{}", toml);
+    }
+}
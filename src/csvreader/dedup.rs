@@ -0,0 +1,99 @@
+use std::{collections::HashSet, fmt::Display};
+
+use super::{CsvData, CsvError, Result};
+
+/// Which of several duplicate records [`CsvData::dedup_by`] keeps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Keep {
+    #[default]
+    First,
+    Last,
+}
+
+impl<T: Display> CsvData<T> {
+    /// Removes records equal to another in every field, keeping the first.
+    /// Returns how many were removed.
+    pub fn dedup(&mut self) -> usize {
+        self.retain_unique(Keep::First, |row| {
+            row.iter().map(ToString::to_string).collect()
+        })
+    }
+
+    /// Removes records whose `columns` equal another's, keeping the first
+    /// or last of each. Fields are compared by their text, a missing one
+    /// being empty, and the kept records stay in file order. Returns how many were removed.
+    pub fn dedup_by(&mut self, columns: &[&str], keep: Keep) -> Result<usize> {
+        let key = columns
+            .iter()
+            .map(|&column| {
+                self.header
+                    .iter()
+                    .position(|name| name == column)
+                    .ok_or_else(|| CsvError::UnknownColumn(column.into()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.retain_unique(keep, |row| {
+            key.iter()
+                .map(|&i| row.get(i).map(ToString::to_string).unwrap_or_default())
+                .collect()
+        }))
+    }
+
+    fn retain_unique(&mut self, keep: Keep, key: impl Fn(&[T]) -> Vec<String>) -> usize {
+        let mut seen = HashSet::new();
+        let mut unique: Vec<bool> = match keep {
+            Keep::First => self.data.iter().map(|row| seen.insert(key(row))).collect(),
+            Keep::Last => self
+                .data
+                .iter()
+                .rev()
+                .map(|row| seen.insert(key(row)))
+                .collect(),
+        };
+        if keep == Keep::Last {
+            unique.reverse();
+        }
+        let before = self.data.len();
+        let mut unique = unique.into_iter();
+        self.data.retain(|_| unique.next().unwrap_or(false));
+        before - self.data.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[&[&str]]) -> CsvData<String> {
+        CsvData {
+            header: vec!["a".into(), "b".into()],
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_first_or_last() {
+        let rows: [&[&str]; 4] = [&["1", "x"], &["2", "y"], &["1", "z"], &["2", "y"]];
+        let mut data = table(&rows);
+        assert_eq!(data.dedup(), 1);
+        assert_eq!(data.data, [["1", "x"], ["2", "y"], ["1", "z"]]);
+
+        let mut data = table(&rows);
+        assert_eq!(data.dedup_by(&["a"], Keep::Last).unwrap(), 2);
+        assert_eq!(data.data, [["1", "z"], ["2", "y"]]);
+        assert!(matches!(
+            data.dedup_by(&["c"], Keep::First),
+            Err(CsvError::UnknownColumn(_))
+        ));
+    }
+
+    #[test]
+    fn missing_fields_are_empty() {
+        let mut data = table(&[&["1"], &["1", ""], &["2", "x"], &[]]);
+        assert_eq!(data.dedup_by(&["b"], Keep::First).unwrap(), 2);
+        assert_eq!(data.data, [vec!["1"], vec!["2", "x"]]);
+    }
+}
@@ -0,0 +1,169 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use super::{tokenizer, CsvError, CsvReadOptions, Severity};
+
+/// Renders `error` together with the offending line of `source` (the text of
+/// the file that was read) and a caret marker under the bad field:
+///
+/// ```text
+/// error: CouldNotParseValue(..)
+///  --> line 4, field 2
+///   |
+/// 4 | 7,x,9
+///   |   ^
+/// ```
+///
+/// Errors without a position are rendered as just the first line.
+pub fn render_diagnostic(error: &CsvError, source: &str) -> String {
+    render_diagnostic_with_delimiter(error, source, ',')
+}
+
+/// [`render_diagnostic`] for a file whose fields are separated by
+/// `delimiter`.
+pub fn render_diagnostic_with_delimiter(error: &CsvError, source: &str, delimiter: char) -> String {
+    let label = match error.severity() {
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    };
+    let mut out = format!("{}: {}", label, error);
+
+    let Some(line_num) = error.line_num() else {
+        return out;
+    };
+    let Some(line) = source.lines().nth(line_num - 1) else {
+        return out;
+    };
+
+    let (start, len) = match (error, error.column()) {
+        (_, Some(column)) => field_span(line, column, delimiter),
+        (CsvError::RecordLengthMismatch(len), _) => match len.boundary {
+            Some(boundary) => {
+                let start = len.raw[..boundary].chars().count();
+                (start, line.chars().count().saturating_sub(start).max(1))
+            }
+            None => (line.chars().count(), 1),
+        },
+        (CsvError::TrailingDelimiter(_), _) => (line.chars().count().saturating_sub(1), 1),
+        _ => (line.chars().count(), 1),
+    };
+
+    let gutter = " ".repeat(line_num.to_string().len());
+    match error.column() {
+        Some(column) => out += &format!("\n{}--> line {}, field {}", gutter, line_num, column + 1),
+        None => out += &format!("\n{}--> line {}", gutter, line_num),
+    }
+    out += &format!("\n{} |", gutter);
+    out += &format!("\n{} | {}", line_num, line);
+    out += &format!("\n{} | {}{}", gutter, " ".repeat(start), "^".repeat(len));
+    out
+}
+
+/// Character offset and width (at least 1) of field `column` in `line`,
+/// quotes included.
+fn field_span(line: &str, column: usize, delimiter: char) -> (usize, usize) {
+    let options = CsvReadOptions::new().delimiter(delimiter);
+    let mut starts = tokenizer::fields(line, &options).map(|(start, _)| start);
+    let Some(start) = starts.nth(column) else {
+        return (line.chars().count(), 1);
+    };
+    let end = starts
+        .next()
+        .map_or(line.len(), |next| next - delimiter.len_utf8());
+    let len = line[start..end].chars().count();
+    (line[..start].chars().count(), len.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::csvreader::parse_csv;
+
+    fn error(source: &str) -> CsvError {
+        match parse_csv::<i64>(source.as_bytes(), &CsvReadOptions::new()) {
+            Err(e) => e,
+            Ok(data) => panic!("expected an error, got {:?}", data),
+        }
+    }
+
+    #[test]
+    fn a_parse_error_points_at_its_field() {
+        let source = "a,b,c\n1,2,3\n7,x,9\n";
+        let e = error(source);
+        assert_eq!((e.line_num(), e.column()), (Some(3), Some(1)));
+        assert_eq!(
+            render_diagnostic(&e, source),
+            format!(
+                "error: {}\n --> line 3, field 2\n  |\n3 | 7,x,9\n  |   ^",
+                e
+            )
+        );
+    }
+
+    #[test]
+    fn quoted_and_wide_fields_are_underlined_whole() {
+        let source = "a;b\n\"é\";\"x y\"\n";
+        let options = CsvReadOptions::new().delimiter(';');
+        let e = parse_csv::<i64>(source.as_bytes(), &options).unwrap_err();
+        let rendered = render_diagnostic_with_delimiter(&e, source, ';');
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(
+            lines[1..],
+            [
+                " --> line 2, field 1",
+                "  |",
+                "2 | \"é\";\"x y\"",
+                "  | ^^^"
+            ]
+        );
+    }
+
+    #[test]
+    fn an_error_without_a_column_points_past_the_record() {
+        let source = "a,b\n1,2\n3\n";
+        let e = error(source);
+        assert_eq!((e.line_num(), e.column()), (Some(3), None));
+        assert_eq!(
+            render_diagnostic(&e, source),
+            format!("error: {}\n --> line 3\n  |\n3 | 3\n  |  ^", e)
+        );
+
+        // The fields a record has too many are underlined.
+        let source = "a,b\n1,2,3,4\n";
+        let rendered = render_diagnostic(&error(source), source);
+        assert!(
+            rendered.ends_with("\n2 | 1,2,3,4\n  |    ^^^^"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn the_gutter_fits_the_line_number() {
+        let mut source = String::from("n\n");
+        for i in 0..8 {
+            source += &format!("{}\n", i);
+        }
+        source += "ten\n";
+        let rendered = render_diagnostic(&error(&source), &source);
+        assert!(
+            rendered.ends_with("\n  --> line 10, field 1\n   |\n10 | ten\n   | ^^^"),
+            "{}",
+            rendered
+        );
+    }
+
+    #[test]
+    fn an_error_without_a_line_is_just_its_message() {
+        let e = error("");
+        assert!(e.line_num().is_none());
+        assert_eq!(render_diagnostic(&e, ""), format!("error: {}", e));
+        // Nor is a line the source doesn't have shown.
+        let e = error("a\nx\n");
+        assert_eq!(render_diagnostic(&e, "a"), format!("error: {}", e));
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::{read_csv_with_options, CsvData, CsvDuplicateKey, CsvError, CsvReadOptions, Result};
+
+/// What changed between two versions of a table, matching records by a
+/// key column. Values are compared by their text.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CsvDiff {
+    pub key_column: String,
+    /// Columns only the new version has.
+    pub added_columns: Vec<String>,
+    /// Columns only the old version has.
+    pub removed_columns: Vec<String>,
+    /// Records only the new version has, in its order and columns.
+    pub added: CsvData<String>,
+    /// Records only the old version has, in its order and columns.
+    pub removed: CsvData<String>,
+    /// Records in both whose values differ in a column both have, in the
+    /// order of the old version.
+    pub changed: Vec<RowChange>,
+}
+
+/// A record whose values changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RowChange {
+    pub key: String,
+    pub changes: Vec<CellChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CellChange {
+    pub column: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl CsvDiff {
+    /// Whether the two versions hold the same records and columns.
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.added.data.is_empty()
+            && self.removed.data.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+/// The differences from the file `old` to the file `new`, whose records are
+/// matched by `key_column`, see [`CsvData::diff`].
+pub fn csv_diff(old: &str, new: &str, key_column: &str) -> Result<CsvDiff> {
+    csv_diff_with_options(old, new, key_column, &CsvReadOptions::default())
+}
+
+/// [`csv_diff`] with explicit options for reading both files, see
+/// [`read_csv_with_options`].
+pub fn csv_diff_with_options(
+    old: &str,
+    new: &str,
+    key_column: &str,
+    options: &CsvReadOptions,
+) -> Result<CsvDiff> {
+    let (old, _) = read_csv_with_options::<String>(old, options)?;
+    let (new, _) = read_csv_with_options::<String>(new, options)?;
+    old.diff(&new, key_column)
+}
+
+impl<T: Display> CsvData<T> {
+    /// The differences from `self` to `new`, whose records are matched by
+    /// `key_column`, which both must have. A key used by more than one
+    /// record of a table fails the diff, with the line number of the
+    /// second, counting the header as line 1.
+    pub fn diff(&self, new: &CsvData<T>, key_column: &str) -> Result<CsvDiff> {
+        let old_keys = keys(self, key_column)?;
+        let new_keys = keys(new, key_column)?;
+        let columns: Vec<(usize, usize)> = self
+            .header
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| Some((i, new.header.iter().position(|n| n == name)?)))
+            .collect();
+
+        let mut removed = Vec::new();
+        let mut changed = Vec::new();
+        for (row, key) in self.data.iter().zip(&old_keys.order) {
+            let Some(&j) = new_keys.rows.get(key) else {
+                removed.push(text(row));
+                continue;
+            };
+            let changes: Vec<CellChange> = columns
+                .iter()
+                .filter_map(|&(i, k)| {
+                    let old = row.get(i).map(ToString::to_string).unwrap_or_default();
+                    let new = new.data[j]
+                        .get(k)
+                        .map(ToString::to_string)
+                        .unwrap_or_default();
+                    (old != new).then(|| CellChange {
+                        column: self.header[i].clone(),
+                        old,
+                        new,
+                    })
+                })
+                .collect();
+            if !changes.is_empty() {
+                changed.push(RowChange {
+                    key: key.clone(),
+                    changes,
+                });
+            }
+        }
+        let added = new
+            .data
+            .iter()
+            .zip(&new_keys.order)
+            .filter(|(_, key)| !old_keys.rows.contains_key(*key))
+            .map(|(row, _)| text(row))
+            .collect();
+
+        let missing_from = |data: &CsvData<T>, other: &CsvData<T>| {
+            data.header
+                .iter()
+                .filter(|name| !other.header.contains(name))
+                .cloned()
+                .collect()
+        };
+        Ok(CsvDiff {
+            key_column: key_column.into(),
+            added_columns: missing_from(new, self),
+            removed_columns: missing_from(self, new),
+            added: CsvData {
+                header: new.header.clone(),
+                data: added,
+            },
+            removed: CsvData {
+                header: self.header.clone(),
+                data: removed,
+            },
+            changed,
+        })
+    }
+}
+
+/// The key of every record of a table, in order, and the record each key
+/// belongs to.
+struct Keys {
+    order: Vec<String>,
+    rows: HashMap<String, usize>,
+}
+
+fn keys<T: Display>(data: &CsvData<T>, key_column: &str) -> Result<Keys> {
+    // ERROR 16: key column is not in the table.
+    let column = data
+        .header
+        .iter()
+        .position(|name| name == key_column)
+        .ok_or_else(|| CsvError::UnknownColumn(key_column.into()))?;
+    let mut keys = Keys {
+        order: Vec::with_capacity(data.data.len()),
+        rows: HashMap::with_capacity(data.data.len()),
+    };
+    for (i, row) in data.data.iter().enumerate() {
+        let key = row.get(column).map(ToString::to_string).unwrap_or_default();
+        if let Some(first) = keys.rows.insert(key.clone(), i) {
+            // ERROR 14: key already used by an earlier record.
+            return Err(CsvError::DuplicateKey(CsvDuplicateKey {
+                line_num: i + 2,
+                columns: vec![key_column.into()],
+                values: vec![key],
+                first_line: first + 2,
+            }));
+        }
+        keys.order.push(key);
+    }
+    Ok(keys)
+}
+
+fn text<T: Display>(row: &[T]) -> Vec<String> {
+    row.iter().map(ToString::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(header: &[&str], rows: &[&[&str]]) -> CsvData<String> {
+        CsvData {
+            header: header.iter().map(|name| name.to_string()).collect(),
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn added_removed_and_changed_records() {
+        let old = table(&["id", "qty"], &[&["1", "5"], &["2", "6"], &["3", "7"]]);
+        let new = table(&["id", "qty"], &[&["3", "7"], &["1", "9"], &["4", "0"]]);
+        let diff = old.diff(&new, "id").unwrap();
+        assert!(!diff.is_empty());
+        assert_eq!(diff.added.data, [["4", "0"]]);
+        assert_eq!(diff.removed.data, [["2", "6"]]);
+        assert_eq!(
+            diff.changed,
+            [RowChange {
+                key: "1".into(),
+                changes: vec![CellChange {
+                    column: "qty".into(),
+                    old: "5".into(),
+                    new: "9".into(),
+                }],
+            }]
+        );
+        assert!(old.diff(&old, "id").unwrap().is_empty());
+    }
+
+    #[test]
+    fn columns_only_one_version_has() {
+        let old = table(&["id", "qty", "note"], &[&["1", "5", "a"]]);
+        let new = table(&["price", "id", "qty"], &[&["2", "1", "6"]]);
+        let diff = old.diff(&new, "id").unwrap();
+        assert_eq!(diff.added_columns, ["price"]);
+        assert_eq!(diff.removed_columns, ["note"]);
+        assert_eq!(diff.changed[0].changes.len(), 1);
+        assert_eq!(diff.added.header, new.header);
+        assert_eq!(diff.removed.header, old.header);
+    }
+
+    #[test]
+    fn keys_must_be_unique_and_present() {
+        let old = table(&["id"], &[&["1"], &["2"], &["1"]]);
+        let new = table(&["key"], &[]);
+        assert!(matches!(
+            old.diff(&old, "id"),
+            Err(CsvError::DuplicateKey(e)) if e.line_num == 4 && e.first_line == 2
+        ));
+        assert!(matches!(
+            table(&["id"], &[]).diff(&new, "id"),
+            Err(CsvError::UnknownColumn(c)) if c == "id"
+        ));
+    }
+}
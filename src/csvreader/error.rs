@@ -0,0 +1,517 @@
+use alloc::{string::String, vec::Vec};
+use core::{error::Error, fmt};
+#[cfg(feature = "std")]
+use std::{
+    boxed::Box,
+    io,
+    path::{Path, PathBuf},
+};
+
+use super::{messages::English, ColumnType, Constraint};
+
+#[derive(Debug)]
+pub struct CsvRecordLen {
+    pub line_num: usize,
+    /// Number of fields in the header.
+    pub expected: usize,
+    pub num_entries: usize,
+    pub raw: String,
+    /// Byte offset into `raw` of the delimiter that starts the first surplus
+    /// field, for records that are too long.
+    pub boundary: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct CsvValuePos {
+    pub line_num: usize,
+    pub column: usize,
+    pub value: String,
+}
+
+/// A value that does not fit its column's declared type.
+#[derive(Debug)]
+pub struct CsvSchemaError {
+    pub line_num: usize,
+    pub column: usize,
+    pub name: String,
+    pub expected: ColumnType,
+    pub value: String,
+}
+
+/// A value that breaks one of its column's constraints.
+#[derive(Debug)]
+pub struct CsvConstraintError {
+    pub line_num: usize,
+    pub column: usize,
+    pub name: String,
+    pub value: String,
+    pub constraint: Constraint,
+}
+
+/// A record that fails one of the schema's row rules.
+#[derive(Debug)]
+pub struct CsvRuleError {
+    pub line_num: usize,
+    pub rule: String,
+}
+
+/// A record the function of `CsvData::par_map_rows` or
+/// `CsvData::par_map_column` failed on.
+#[derive(Debug)]
+pub struct CsvMapError {
+    pub line_num: usize,
+    /// The 0-based index and name of the column, for a single mapped value.
+    pub column: Option<(usize, String)>,
+    pub reason: String,
+}
+
+/// Two records with the same value for a unique key.
+#[derive(Debug)]
+pub struct CsvDuplicateKey {
+    pub line_num: usize,
+    /// The line the key was first seen on.
+    pub first_line: usize,
+    pub columns: Vec<String>,
+    pub values: Vec<String>,
+}
+
+/// How a file's header differs from the expected one.
+#[derive(Debug)]
+pub struct CsvHeaderDiff {
+    pub missing: Vec<String>,
+    pub unexpected: Vec<String>,
+    /// Columns present in both, but not in the expected order.
+    pub reordered: Vec<String>,
+}
+
+/// A configuration file that could not be used.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct CsvConfigError {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// A [`Filter`](super::Filter) that could not be parsed.
+#[derive(Debug)]
+pub struct CsvFilterError {
+    pub filter: String,
+    /// The byte offset in `filter` the problem was found at.
+    pub offset: usize,
+    pub reason: String,
+}
+
+/// A query that could not be run, or whose rows could not be read.
+#[derive(Debug)]
+pub struct CsvQueryError {
+    pub query: String,
+    pub reason: String,
+}
+
+/// A problem with one of several files read together.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct CsvInFileError {
+    pub path: PathBuf,
+    pub error: Box<CsvError>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct CsvFileError {
+    pub path: PathBuf,
+    pub source: io::Error,
+}
+
+/// Everything that can go wrong reading a file. New kinds of problems are
+/// added as new variants, so matches need a fallback arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CsvError {
+    #[cfg(feature = "std")]
+    FileNotFound(PathBuf),
+    #[cfg(feature = "std")]
+    PermissionDenied(PathBuf),
+    #[cfg(feature = "std")]
+    IsADirectory(PathBuf),
+    #[cfg(feature = "std")]
+    CouldNotOpenFile(CsvFileError),
+    #[cfg(feature = "std")]
+    CouldNotWriteFile(CsvFileError),
+    #[cfg(feature = "std")]
+    CouldNotParseLine(Box<dyn Error + Send + Sync>),
+    FileIsEmpty,
+    CouldNotParseValue(CsvValuePos),
+    RecordLengthMismatch(CsvRecordLen),
+    ByteOrderMark,
+    InvalidUtf8(usize),
+    TrailingDelimiter(usize),
+    EmptyValue(CsvValuePos),
+    HeaderMismatch(CsvHeaderDiff),
+    DuplicateHeader(Vec<String>),
+    TypeMismatch(CsvSchemaError),
+    NotNullable(CsvSchemaError),
+    ConstraintViolation(CsvConstraintError),
+    DuplicateKey(CsvDuplicateKey),
+    RuleViolation(CsvRuleError),
+    #[cfg(feature = "std")]
+    InvalidConfig(CsvConfigError),
+    UnknownColumn(String),
+    InvalidFilter(CsvFilterError),
+    UnknownSheet(String),
+    QueryFailed(CsvQueryError),
+    MapFailed(CsvMapError),
+    #[cfg(feature = "std")]
+    InFile(CsvInFileError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    /// The reader can recover, see [`Strictness`](super::Strictness).
+    Warning,
+    Error,
+}
+
+/// Broad kind of failure, for callers that branch on it rather than on the
+/// exact variant.
+///
+/// Each category maps to a process exit code via [`ErrorCategory::exit_code`]:
+///
+/// | category     | exit code |
+/// |--------------|-----------|
+/// | `Config`     | 2         |
+/// | `Io`         | 3         |
+/// | `Format`     | 4         |
+/// | `Parse`      | 5         |
+/// | `Validation` | 6         |
+/// | `Limit`      | 7         |
+///
+/// `0` means success, `1` is left for unexpected failures and `2` doubles as
+/// the code for other usage errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// The reader configuration is invalid.
+    Config,
+    /// The file could not be opened, read or written, or a database query
+    /// could not be run.
+    Io,
+    /// The file is not well-formed CSV.
+    Format,
+    /// A value could not be parsed into the requested type.
+    Parse,
+    /// The file is well-formed, but not what was expected.
+    Validation,
+    /// Reading stopped because a configured limit was hit.
+    Limit,
+}
+
+impl ErrorCategory {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Config => 2,
+            Self::Io => 3,
+            Self::Format => 4,
+            Self::Parse => 5,
+            Self::Validation => 6,
+            Self::Limit => 7,
+        }
+    }
+}
+
+impl CsvError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            #[cfg(feature = "std")]
+            Self::InFile(e) => e.error.category(),
+            #[cfg(feature = "std")]
+            Self::InvalidConfig(_) => ErrorCategory::Config,
+            Self::InvalidFilter(_) => ErrorCategory::Config,
+            // Running the query reads the database, so it fails like a read.
+            Self::QueryFailed(_) => ErrorCategory::Io,
+            #[cfg(feature = "std")]
+            Self::FileNotFound(_)
+            | Self::PermissionDenied(_)
+            | Self::IsADirectory(_)
+            | Self::CouldNotOpenFile(_)
+            | Self::CouldNotWriteFile(_)
+            | Self::CouldNotParseLine(_) => ErrorCategory::Io,
+            Self::FileIsEmpty
+            | Self::RecordLengthMismatch(_)
+            | Self::ByteOrderMark
+            | Self::InvalidUtf8(_)
+            | Self::TrailingDelimiter(_)
+            | Self::DuplicateHeader(_) => ErrorCategory::Format,
+            Self::CouldNotParseValue(_) | Self::EmptyValue(_) | Self::TypeMismatch(_) => {
+                ErrorCategory::Parse
+            }
+            Self::HeaderMismatch(_)
+            | Self::NotNullable(_)
+            | Self::ConstraintViolation(_)
+            | Self::DuplicateKey(_)
+            | Self::RuleViolation(_)
+            | Self::MapFailed(_)
+            | Self::UnknownColumn(_)
+            | Self::UnknownSheet(_) => ErrorCategory::Validation,
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            #[cfg(feature = "std")]
+            Self::InFile(e) => e.error.severity(),
+            Self::ByteOrderMark
+            | Self::InvalidUtf8(_)
+            | Self::TrailingDelimiter(_)
+            | Self::EmptyValue(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// A stable, machine-readable name for the kind of problem.
+    pub fn code(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "std")]
+            Self::FileNotFound(_) => "file_not_found",
+            #[cfg(feature = "std")]
+            Self::PermissionDenied(_) => "permission_denied",
+            #[cfg(feature = "std")]
+            Self::IsADirectory(_) => "is_a_directory",
+            #[cfg(feature = "std")]
+            Self::CouldNotOpenFile(_) => "could_not_open_file",
+            #[cfg(feature = "std")]
+            Self::CouldNotWriteFile(_) => "could_not_write_file",
+            #[cfg(feature = "std")]
+            Self::CouldNotParseLine(_) => "could_not_parse_line",
+            Self::FileIsEmpty => "file_is_empty",
+            Self::CouldNotParseValue(_) => "could_not_parse_value",
+            Self::RecordLengthMismatch(_) => "record_length_mismatch",
+            Self::ByteOrderMark => "byte_order_mark",
+            Self::InvalidUtf8(_) => "invalid_utf8",
+            Self::TrailingDelimiter(_) => "trailing_delimiter",
+            Self::EmptyValue(_) => "empty_value",
+            Self::HeaderMismatch(_) => "header_mismatch",
+            Self::DuplicateHeader(_) => "duplicate_header",
+            Self::TypeMismatch(_) => "type_mismatch",
+            Self::NotNullable(_) => "not_nullable",
+            Self::ConstraintViolation(_) => "constraint_violation",
+            Self::DuplicateKey(_) => "duplicate_key",
+            Self::RuleViolation(_) => "rule_violation",
+            #[cfg(feature = "std")]
+            Self::InvalidConfig(_) => "invalid_config",
+            Self::UnknownColumn(_) => "unknown_column",
+            Self::InvalidFilter(_) => "invalid_filter",
+            Self::UnknownSheet(_) => "unknown_sheet",
+            Self::QueryFailed(_) => "query_failed",
+            Self::MapFailed(_) => "map_failed",
+            #[cfg(feature = "std")]
+            Self::InFile(e) => e.error.code(),
+        }
+    }
+
+    /// The 1-based line the problem was found on, if it is tied to one.
+    pub fn line_num(&self) -> Option<usize> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::InFile(e) => e.error.line_num(),
+            Self::CouldNotParseValue(pos) | Self::EmptyValue(pos) => Some(pos.line_num),
+            Self::RecordLengthMismatch(len) => Some(len.line_num),
+            Self::InvalidUtf8(line_num) | Self::TrailingDelimiter(line_num) => Some(*line_num),
+            Self::TypeMismatch(e) | Self::NotNullable(e) => Some(e.line_num),
+            Self::ConstraintViolation(e) => Some(e.line_num),
+            Self::DuplicateKey(e) => Some(e.line_num),
+            Self::RuleViolation(e) => Some(e.line_num),
+            Self::MapFailed(e) => Some(e.line_num),
+            Self::ByteOrderMark
+            | Self::HeaderMismatch(_)
+            | Self::DuplicateHeader(_)
+            | Self::UnknownColumn(_) => Some(1),
+            _ => None,
+        }
+    }
+
+    /// The 0-based index of the offending field, if it is tied to one.
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::InFile(e) => e.error.column(),
+            Self::CouldNotParseValue(pos) | Self::EmptyValue(pos) => Some(pos.column),
+            Self::TypeMismatch(e) | Self::NotNullable(e) => Some(e.column),
+            Self::ConstraintViolation(e) => Some(e.column),
+            Self::MapFailed(e) => e.column.as_ref().map(|&(column, _)| column),
+            Self::ByteOrderMark => Some(0),
+            _ => None,
+        }
+    }
+
+    /// The file the problem is about, for failures to open it and problems
+    /// with one of several files.
+    #[cfg(feature = "std")]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::FileNotFound(path) | Self::PermissionDenied(path) | Self::IsADirectory(path) => {
+                Some(path)
+            }
+            Self::CouldNotOpenFile(e) | Self::CouldNotWriteFile(e) => Some(&e.path),
+            Self::InvalidConfig(e) => Some(&e.path),
+            Self::InFile(e) => Some(&e.path),
+            _ => None,
+        }
+    }
+
+    /// The raw text of the offending field, if it is tied to one.
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::InFile(e) => e.error.value(),
+            Self::CouldNotParseValue(pos) | Self::EmptyValue(pos) => Some(&pos.value),
+            Self::TypeMismatch(e) | Self::NotNullable(e) => Some(&e.value),
+            Self::ConstraintViolation(e) => Some(&e.value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl CsvFileError {
+    /// Maps a failure to open `path` to the matching [`CsvError`] variant.
+    pub(super) fn into_error(path: &Path, source: io::Error) -> CsvError {
+        let path = path.to_path_buf();
+        match source.kind() {
+            io::ErrorKind::NotFound => CsvError::FileNotFound(path),
+            io::ErrorKind::PermissionDenied => CsvError::PermissionDenied(path),
+            io::ErrorKind::IsADirectory => CsvError::IsADirectory(path),
+            _ => CsvError::CouldNotOpenFile(CsvFileError { path, source }),
+        }
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.localize(&English))
+    }
+}
+
+impl Error for CsvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::CouldNotOpenFile(e) | Self::CouldNotWriteFile(e) => Some(&e.source),
+            #[cfg(feature = "std")]
+            Self::CouldNotParseLine(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// A record left out of the result by [`OnError::Skip`](super::OnError::Skip).
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SkippedRecord {
+    #[cfg_attr(feature = "serde", serde(rename = "line"))]
+    pub line_num: usize,
+    /// The first problem found in the record.
+    pub reason: CsvError,
+    pub raw: String,
+}
+
+/// Problems found while reading a file. `errors` is only ever filled in
+/// collect-all mode, `warnings` whenever
+/// [`Strictness::Warn`](super::Strictness::Warn) is used and `skipped` with
+/// [`OnError::Skip`](super::OnError::Skip).
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CsvErrorReport {
+    /// Number of records read, including ones that were left out.
+    pub records: usize,
+    pub errors: Vec<CsvError>,
+    pub warnings: Vec<CsvError>,
+    pub skipped: Vec<SkippedRecord>,
+    /// Set when reading stopped early because `max_errors` was reached.
+    pub truncated: bool,
+}
+
+impl CsvErrorReport {
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty() && self.skipped.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len() + self.warnings.len() + self.skipped.len()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// [`ErrorCategory::Limit`] if the report was truncated, otherwise the
+    /// category of the first error. `None` if there are no errors.
+    pub fn category(&self) -> Option<ErrorCategory> {
+        if self.truncated {
+            return Some(ErrorCategory::Limit);
+        }
+        self.errors.first().map(CsvError::category)
+    }
+}
+
+impl fmt::Display for CsvErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} error(s), {} warning(s), {} skipped record(s) found",
+            self.errors.len(),
+            self.warnings.len(),
+            self.skipped.len()
+        )?;
+        for error in &self.errors {
+            write!(f, "\n  - error: {}", error)?;
+        }
+        for warning in &self.warnings {
+            write!(f, "\n  - warning: {}", warning)?;
+        }
+        for skipped in &self.skipped {
+            write!(
+                f,
+                "\n  - skipped line {}: {} ({:?})",
+                skipped.line_num, skipped.reason, skipped.raw
+            )?;
+        }
+        if self.truncated {
+            write!(f, "\n  (stopped after {} problems)", self.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for CsvErrorReport {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_errors_are_the_source() {
+        let e = CsvError::CouldNotWriteFile(CsvFileError {
+            path: PathBuf::from("out.csv"),
+            source: io::Error::other("disk full"),
+        });
+        assert_eq!(e.source().unwrap().to_string(), "disk full");
+
+        let e = CsvError::CouldNotParseLine(Box::new(io::Error::other("bad sector")));
+        let source = e.source().unwrap();
+        assert!(source.downcast_ref::<io::Error>().is_some());
+        assert_eq!(source.to_string(), "bad sector");
+
+        assert!(CsvError::FileIsEmpty.source().is_none());
+    }
+}
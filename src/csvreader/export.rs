@@ -0,0 +1,132 @@
+use std::io::{self, Write};
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{json, Map};
+
+use super::{ColumnSchema, ColumnType, Constraint, CsvData, CsvSchema};
+
+/// Writes `data` as a JSON array with one object per record, keyed by
+/// column name in header order.
+pub fn write_json<T: Serialize>(data: &CsvData<T>, mut out: impl Write) -> io::Result<()> {
+    let records: Vec<_> = records(data).collect();
+    serde_json::to_writer_pretty(&mut out, &records)?;
+    writeln!(out)
+}
+
+/// Writes `data` as JSON Lines: one object per record, like [`write_json`].
+pub fn write_jsonl<T: Serialize>(data: &CsvData<T>, mut out: impl Write) -> io::Result<()> {
+    for record in records(data) {
+        serde_json::to_writer(&mut out, &record)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn records<T>(data: &CsvData<T>) -> impl Iterator<Item = Record<'_, T>> {
+    data.data.iter().map(|values| Record {
+        header: &data.header,
+        values,
+    })
+}
+
+/// A record serialized as a map, keeping the column order.
+struct Record<'a, T> {
+    header: &'a [String],
+    values: &'a [T],
+}
+
+impl<T: Serialize> Serialize for Record<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.header.len()))?;
+        for (name, value) in self.header.iter().zip(self.values) {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl CsvSchema {
+    /// A JSON Schema (draft 2020-12) for one record as an object keyed by
+    /// column name. Row rules can't be expressed and are left out. Needs the
+    /// `serde` feature.
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        let properties: Map<_, _> = self
+            .columns
+            .iter()
+            .map(|column| (column.name.clone(), json_schema_column(column)))
+            .collect();
+        let required: Vec<_> = self
+            .columns
+            .iter()
+            .filter(|c| c.required)
+            .map(|c| c.name.as_str())
+            .collect();
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+
+    /// A Frictionless Data table schema, as used in `datapackage.json`. The
+    /// first unique key becomes the primary key. Row rules can't be
+    /// expressed and are left out.
+    pub fn to_table_schema(&self) -> serde_json::Value {
+        let fields: Vec<_> = self.columns.iter().map(table_schema_field).collect();
+        let mut schema = json!({ "fields": fields, "missingValues": [""] });
+        if let Some(key) = self.unique_keys.first() {
+            schema["primaryKey"] = json!(key);
+        }
+        if self.unique_keys.len() > 1 {
+            schema["uniqueKeys"] = json!(self.unique_keys[1..]);
+        }
+        schema
+    }
+}
+
+fn json_schema_column(column: &ColumnSchema) -> serde_json::Value {
+    let ty = json_type(column.column_type);
+    let mut schema = match column.nullable {
+        true => json!({ "type": [ty, "null"] }),
+        false => json!({ "type": ty }),
+    };
+    if !column.required {
+        schema["default"] = json!(column.default);
+    }
+    add_constraints(&mut schema, &column.constraints);
+    schema
+}
+
+fn table_schema_field(column: &ColumnSchema) -> serde_json::Value {
+    let mut constraints = json!({ "required": !column.nullable });
+    add_constraints(&mut constraints, &column.constraints);
+    json!({
+        "name": column.name,
+        "type": json_type(column.column_type),
+        "constraints": constraints,
+    })
+}
+
+/// Both formats use the JSON Schema type names and constraint keywords.
+fn json_type(ty: ColumnType) -> &'static str {
+    match ty {
+        ColumnType::Bool => "boolean",
+        ColumnType::Int => "integer",
+        ColumnType::Float => "number",
+        ColumnType::Text => "string",
+    }
+}
+
+fn add_constraints(target: &mut serde_json::Value, constraints: &[Constraint]) {
+    for constraint in constraints {
+        match constraint {
+            Constraint::Min(min) => target["minimum"] = json!(min),
+            Constraint::Max(max) => target["maximum"] = json!(max),
+            #[cfg(feature = "regex")]
+            Constraint::Pattern(re) => target["pattern"] = json!(re.as_str()),
+            Constraint::MaxLength(len) => target["maxLength"] = json!(len),
+        }
+    }
+}
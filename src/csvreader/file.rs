@@ -0,0 +1,396 @@
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Read},
+    iter, mem,
+    path::Path,
+    str::FromStr,
+};
+
+use super::{
+    line_breaks, parse_lines, parse_lines_flat, split_lines, tokenizer::Scanner, CsvData, CsvError,
+    CsvErrorReport, CsvFileError, CsvReadOptions, Engine, FieldParser, FlatCsvData, FromStrParser,
+    Result, Sink,
+};
+
+/// Reads `filename` with the default options, a shortcut for
+/// [`CsvReaderBuilder`](super::CsvReaderBuilder), which configures reads.
+pub fn read_csv<T: Default + FromStr>(filename: &str) -> Result<CsvData<T>> {
+    read_csv_with_options(filename, &CsvReadOptions::default()).map(|(data, _)| data)
+}
+
+/// Like [`read_csv`], but with explicit options. Errors are handled according
+/// to `options.on_error`, warnings according to `options.strictness`; both
+/// end up in the returned report when they don't abort the read.
+pub fn read_csv_with_options<T: Default + FromStr>(
+    filename: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    read(filename, options, false, &mut FromStrParser)
+}
+
+/// Like [`read_csv`], but keeps going past bad lines: the good rows are
+/// returned together with a report of every problem that was found.
+/// Only file-level failures (missing, unreadable, empty) are returned as `Err`.
+pub fn read_csv_validated<T: Default + FromStr>(
+    filename: &str,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    read_csv_validated_with_options(filename, &CsvReadOptions::default())
+}
+
+pub fn read_csv_validated_with_options<T: Default + FromStr>(
+    filename: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    read(filename, options, true, &mut FromStrParser)
+}
+
+/// Like [`read_csv`], but stores the records in one buffer instead of one
+/// per record, see [`FlatCsvData`].
+pub fn read_csv_flat<T: Default + FromStr>(filename: &str) -> Result<FlatCsvData<T>> {
+    read_csv_flat_with_options(filename, &CsvReadOptions::default()).map(|(data, _)| data)
+}
+
+pub fn read_csv_flat_with_options<T: Default + FromStr>(
+    filename: &str,
+    options: &CsvReadOptions,
+) -> Result<(FlatCsvData<T>, CsvErrorReport)> {
+    let bytes = read_bytes(filename)?;
+    let sink = Sink::new(options, false);
+    parse_lines_flat(&split_lines(&bytes, options), sink, &mut FromStrParser)
+}
+
+/// Like [`read_csv`], but fails before parsing any record if the header is
+/// not exactly `expected`, listing the missing, unexpected and reordered
+/// columns.
+pub fn read_csv_expect_header<T: Default + FromStr>(
+    filename: &str,
+    expected: &[&str],
+) -> Result<CsvData<T>> {
+    let options = CsvReadOptions::new().expect_header(expected);
+    read_csv_with_options(filename, &options).map(|(data, _)| data)
+}
+
+/// Opens `filename` and reads its header, returning an iterator that parses
+/// the records one at a time as they are read, instead of loading the whole
+/// file. Problems are handled according to `options`, like in
+/// [`read_csv_with_options`]; the iterator ends after the first error that
+/// aborts.
+pub fn stream_csv<'a, T: Default + FromStr>(
+    filename: &str,
+    options: &'a CsvReadOptions,
+) -> Result<CsvRecords<'a, T>> {
+    let mut reader = BufReader::new(open_file(filename)?);
+    let mut line = Vec::new();
+    // ERROR 4: file was empty
+    if !read_record(&mut reader, options, &mut line)? {
+        return Err(CsvError::FileIsEmpty);
+    }
+    let mut more = Vec::new();
+    for _ in 1..options.header_lines() {
+        let mut row = Vec::new();
+        if !read_record(&mut reader, options, &mut row)? {
+            break;
+        }
+        more.push(row);
+    }
+    let mut parser: Box<dyn FieldParser<T>> = Box::new(FromStrParser);
+    let sink = Sink::new(options, false);
+    let (engine, first) = Engine::start(&line, &more, sink, parser.as_mut())?;
+    let rows = iter::once(&line).chain(&more);
+    let mut line_num = 1 + rows.map(|row| 1 + line_breaks(row)).sum::<usize>();
+    // Without a header the line is kept as the first record, minus any BOM.
+    let mut ahead = VecDeque::new();
+    if let Some(first) = first {
+        let bom = line.len() - first.len();
+        line.drain(..bom);
+        line_num = 2 + line_breaks(&line);
+        ahead.push_back((1, line));
+    }
+    Ok(CsvRecords {
+        reader,
+        options,
+        ahead,
+        ready: 0,
+        footer_run: 0,
+        eof: false,
+        spare: Vec::new(),
+        engine,
+        parser,
+        line_num,
+        finished: false,
+    })
+}
+
+/// The records of a file, see [`stream_csv`].
+pub struct CsvRecords<'a, T> {
+    reader: BufReader<File>,
+    options: &'a CsvReadOptions,
+    /// Records read but not parsed yet, with their line numbers. Only as
+    /// many are read ahead as it takes to tell whether they are part of the
+    /// footer.
+    ahead: VecDeque<(usize, Vec<u8>)>,
+    /// Number of records at the front of `ahead` known not to be footer.
+    ready: usize,
+    /// Number of records after those that pass the footer test and have at
+    /// least `skip_footer` records after them.
+    footer_run: usize,
+    eof: bool,
+    /// The last record parsed, whose buffer is reused for the next one.
+    spare: Vec<u8>,
+    engine: Engine<'a>,
+    parser: Box<dyn FieldParser<T>>,
+    /// Line number of the next line to read.
+    line_num: usize,
+    finished: bool,
+}
+
+impl<T> CsvRecords<'_, T> {
+    pub fn header(&self) -> &[String] {
+        &self.engine.header
+    }
+
+    /// Problems recovered from so far.
+    pub fn report(&self) -> &CsvErrorReport {
+        &self.engine.sink.report
+    }
+
+    pub fn into_report(self) -> CsvErrorReport {
+        self.engine.sink.report
+    }
+
+    /// Reads records until one is known not to be footer, or the file ends.
+    /// A record isn't footer if it has `skip_footer` records after it and
+    /// fails the footer test; then neither are those before it.
+    fn look_ahead(&mut self) -> Result<()> {
+        let skip = self.options.skip_footer;
+        loop {
+            while self.footer_run + skip < self.ahead.len() {
+                let (_, raw) = &self.ahead[self.footer_run];
+                if !self.options.is_footer(raw) {
+                    self.ready = self.footer_run + 1;
+                    self.footer_run = 0;
+                    return Ok(());
+                }
+                self.footer_run += 1;
+            }
+            // At the end of the file, all that is left is footer.
+            if self.eof {
+                return Ok(());
+            }
+            let mut raw = mem::take(&mut self.spare);
+            if !read_record(&mut self.reader, self.options, &mut raw)? {
+                self.eof = true;
+                continue;
+            }
+            let line_num = self.line_num;
+            self.line_num += 1 + line_breaks(&raw);
+            self.ahead.push_back((line_num, raw));
+        }
+    }
+}
+
+impl<T: Default> Iterator for CsvRecords<'_, T> {
+    type Item = Result<Vec<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            if self.ready == 0 {
+                if let Err(e) = self.look_ahead() {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+                // Only footer is left.
+                if self.ready == 0 {
+                    return None;
+                }
+            }
+            let (line_num, raw) = self.ahead.pop_front()?;
+            self.ready -= 1;
+            let record = self.engine.record(line_num, &raw, self.parser.as_mut());
+            self.spare = raw;
+            self.finished = record.is_err() || self.engine.done();
+            if let Some(record) = record.transpose() {
+                return Some(record);
+            }
+        }
+        None
+    }
+}
+
+pub(super) fn read<T: Default>(
+    filename: &str,
+    options: &CsvReadOptions,
+    collect_errors: bool,
+    parser: &mut dyn FieldParser<T>,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!("read_csv", path = filename, collect_errors).entered();
+    #[cfg(feature = "tracing")]
+    let start = std::time::Instant::now();
+
+    let bytes = read_bytes(filename)?;
+    let sink = Sink::new(options, collect_errors);
+    let (data, report) = parse_lines(&split_lines(&bytes, options), sink, parser)?;
+
+    #[cfg(feature = "tracing")]
+    {
+        let secs = start.elapsed().as_secs_f64();
+        tracing::info!(
+            records = data.data.len(),
+            errors = report.errors.len(),
+            warnings = report.warnings.len(),
+            skipped = report.skipped.len(),
+            truncated = report.truncated,
+            secs,
+            records_per_sec = data.data.len() as f64 / secs.max(f64::EPSILON),
+            "finished reading"
+        );
+    }
+    Ok((data, report))
+}
+
+/// The whole of `filename`, read into a buffer sized from its length.
+pub(super) fn read_bytes(filename: &str) -> Result<Vec<u8>> {
+    let mut file = open_file(filename)?;
+    let mut bytes = Vec::new();
+    // ERROR 3: file could not be read.
+    file.read_to_end(&mut bytes)
+        .map_err(|e| CsvError::CouldNotParseLine(Box::new(e)))?;
+    Ok(bytes)
+}
+
+pub(super) fn open_file(filename: &str) -> Result<File> {
+    let path = Path::new(filename);
+    // ERROR 1: file could be missing, inaccessible or a directory.
+    let file = OpenOptions::new()
+        .read(true)
+        .open(path)
+        .map_err(|e| CsvFileError::into_error(path, e))?;
+    // Opening a directory for reading succeeds on some platforms.
+    let metadata = file
+        .metadata()
+        .map_err(|e| CsvFileError::into_error(path, e))?;
+    if metadata.is_dir() {
+        return Err(CsvError::IsADirectory(path.to_path_buf()));
+    }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(bytes = metadata.len(), "opened file");
+    Ok(file)
+}
+
+/// Reads the next record into `line`, without its `\n` or `\r\n`, going
+/// on past line breaks in quoted fields. Returns `false` at the end of the
+/// file.
+fn read_record(
+    reader: &mut impl BufRead,
+    options: &CsvReadOptions,
+    line: &mut Vec<u8>,
+) -> Result<bool> {
+    line.clear();
+    let mut scanner = Scanner::new(options);
+    loop {
+        let start = line.len();
+        // ERROR 3: line could not be parsed.
+        let read = reader
+            .read_until(b'\n', line)
+            .map_err(|e| CsvError::CouldNotParseLine(Box::new(e)))?;
+        // An unclosed quote runs to the end of the file.
+        if read == 0 && start == 0 {
+            return Ok(false);
+        }
+        if read == 0 || scanner.record_end(&line[start..]).is_some() {
+            line.pop_if(|&mut b| b == b'\n');
+            break;
+        }
+        if !line.ends_with(b"\n") {
+            break;
+        }
+    }
+    if line.ends_with(b"\r") {
+        line.pop();
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::{testutil::TempFile, OnError};
+
+    /// The records of `contents` and the lines of those skipped, streamed
+    /// and read whole, which must agree.
+    fn both(contents: &str, options: CsvReadOptions) -> (Vec<Vec<i64>>, Vec<usize>) {
+        let file = TempFile::new("footer.csv", contents);
+        let options = options.on_error(OnError::Skip);
+        let (data, report) = read_csv_with_options::<i64>(file.path(), &options).unwrap();
+        let read_lines: Vec<usize> = report.skipped.iter().map(|s| s.line_num).collect();
+
+        let mut records = stream_csv::<i64>(file.path(), &options).unwrap();
+        let streamed: Vec<Vec<i64>> = records.by_ref().map(Result::unwrap).collect();
+        let report = records.into_report();
+        let streamed_lines: Vec<usize> = report.skipped.iter().map(|s| s.line_num).collect();
+
+        assert_eq!(streamed, data.data);
+        assert_eq!(streamed_lines, read_lines);
+        (data.data, read_lines)
+    }
+
+    fn totals() -> CsvReadOptions {
+        CsvReadOptions::new().footer_when(|raw| raw.starts_with("total"))
+    }
+
+    const FILE: &str = "a,b\n1,2\nx,4\n5,6\n7,8\n";
+
+    #[test]
+    fn skip_footer_alone() {
+        let (data, skipped) = both(FILE, CsvReadOptions::new().skip_footer(2));
+        assert_eq!(data, [[1, 2]]);
+        assert_eq!(skipped, [3]);
+        let (data, _) = both(FILE, CsvReadOptions::new().skip_footer(0));
+        assert_eq!(data, [[1, 2], [5, 6], [7, 8]]);
+    }
+
+    #[test]
+    fn footer_shorter_than_skip_footer() {
+        let contents = "a,b\n1,2\n3,4\n5,6\ntotal,12\n";
+        let (data, _) = both(contents, totals().skip_footer(2));
+        assert_eq!(data, [[1, 2], [3, 4]]);
+    }
+
+    #[test]
+    fn footer_as_long_as_skip_footer() {
+        let contents = "a,b\n1,2\n3,4\ntotal,4\ntotal,6\n";
+        let (data, _) = both(contents, totals().skip_footer(2));
+        assert_eq!(data, [[1, 2], [3, 4]]);
+    }
+
+    #[test]
+    fn footer_longer_than_skip_footer() {
+        let contents = "a,b\n1,2\ntotal,1\nx,4\ntotal,2\ntotal,3\nnote\n";
+        let (data, skipped) = both(contents, totals().skip_footer(1));
+        // The `total` record followed by others isn't footer.
+        assert_eq!(data, [[1, 2]]);
+        assert_eq!(skipped, [3, 4]);
+    }
+
+    #[test]
+    fn footer_with_line_breaks_in_quotes() {
+        let contents = "a,b\n1,2\n\"x\ny\",4\n5,6\n\"total\n\",1\n";
+        let options = CsvReadOptions::new().footer_when(|raw| raw.starts_with("\"total"));
+        let (data, skipped) = both(contents, options);
+        assert_eq!(data, [[1, 2], [5, 6]]);
+        assert_eq!(skipped, [3]);
+    }
+
+    #[test]
+    fn only_footer() {
+        let contents = "a,b\ntotal,1\ntotal,2\n";
+        assert!(both(contents, totals()).0.is_empty());
+        assert!(both(contents, totals().skip_footer(1)).0.is_empty());
+        assert!(both(contents, CsvReadOptions::new().skip_footer(5))
+            .0
+            .is_empty());
+        assert!(both("a,b\n", totals().skip_footer(1)).0.is_empty());
+    }
+}
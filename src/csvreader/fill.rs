@@ -0,0 +1,187 @@
+use super::{ColumnType, CsvData, CsvError, CsvSchemaError, Result, Value};
+
+/// How [`CsvData::fill_missing`] replaces the nulls of a column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Strategy {
+    /// The last value above, if there is one.
+    ForwardFill,
+    /// The straight line between the nearest numbers above and below, by
+    /// record; gaps at the start or end of the column stay null. Between
+    /// two integers the value is an integer where it is a whole number.
+    Linear,
+    /// The same value everywhere.
+    Constant(Value),
+}
+
+impl CsvData<Value> {
+    /// Replaces the nulls of `column` as `strategy` says, such as the gaps
+    /// in a series of sensor readings. Returns how many were filled; those
+    /// the strategy has no value for stay null.
+    ///
+    /// With [`Strategy::Linear`] every other value of the column must be a
+    /// number.
+    pub fn fill_missing(&mut self, column: &str, strategy: Strategy) -> Result<usize> {
+        let i = self
+            .header
+            .iter()
+            .position(|name| name == column)
+            // ERROR 16: column is not in the table.
+            .ok_or_else(|| CsvError::UnknownColumn(column.into()))?;
+        let mut values: Vec<Value> = self
+            .data
+            .iter()
+            .map(|row| row.get(i).cloned().unwrap_or_default())
+            .collect();
+        let filled = match strategy {
+            Strategy::ForwardFill => forward_fill(&mut values),
+            Strategy::Linear => self.interpolate(i, &mut values)?,
+            Strategy::Constant(value) => {
+                let nulls = values.iter_mut().filter(|v| v.is_null());
+                nulls.map(|v| *v = value.clone()).count()
+            }
+        };
+        for (row, value) in self.data.iter_mut().zip(values) {
+            if row.len() <= i {
+                row.resize(i + 1, Value::Null);
+            }
+            row[i] = value;
+        }
+        Ok(filled)
+    }
+
+    /// Fills the gaps of `values`, column `i` of the table, linearly.
+    fn interpolate(&self, i: usize, values: &mut [Value]) -> Result<usize> {
+        if let Some((row, value)) = values
+            .iter()
+            .enumerate()
+            .find(|(_, v)| !v.is_null() && v.as_f64().is_none())
+        {
+            // ERROR 10: value is not a number.
+            return Err(CsvError::TypeMismatch(CsvSchemaError {
+                line_num: row + 2,
+                column: i,
+                name: self.header[i].clone(),
+                expected: ColumnType::Float,
+                value: value.to_string(),
+            }));
+        }
+        let mut filled = 0;
+        let mut last: Option<usize> = None;
+        for next in 0..values.len() {
+            if values[next].is_null() {
+                continue;
+            }
+            if let Some(last) = last.filter(|&last| next > last + 1) {
+                let (start, end) = (values[last].clone(), values[next].clone());
+                for (gap, value) in values[last + 1..next].iter_mut().enumerate() {
+                    let t = (gap + 1) as f64 / (next - last) as f64;
+                    *value = between(&start, &end, t);
+                }
+                filled += next - last - 1;
+            }
+            last = Some(next);
+        }
+        Ok(filled)
+    }
+}
+
+/// Fills each null of `values` with the last value above it.
+fn forward_fill(values: &mut [Value]) -> usize {
+    let mut filled = 0;
+    let mut last: Option<Value> = None;
+    for value in values {
+        match (value.is_null(), &last) {
+            (true, Some(last)) => {
+                *value = last.clone();
+                filled += 1;
+            }
+            (true, None) => {}
+            (false, _) => last = Some(value.clone()),
+        }
+    }
+    filled
+}
+
+/// The value a fraction `t` of the way from `start` to `end`.
+fn between(start: &Value, end: &Value, t: f64) -> Value {
+    let (a, b) = (
+        start.as_f64().unwrap_or_default(),
+        end.as_f64().unwrap_or_default(),
+    );
+    let x = a + (b - a) * t;
+    match (start, end) {
+        (Value::Int(_), Value::Int(_)) if x.fract() == 0.0 => Value::Int(x as i64),
+        _ => Value::Float(x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(values: &[Option<i64>]) -> CsvData<Value> {
+        CsvData {
+            header: vec!["id".into(), "x".into()],
+            data: values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| vec![Value::Int(i as i64), v.map_or(Value::Null, Value::Int)])
+                .collect(),
+        }
+    }
+
+    fn values(data: &CsvData<Value>) -> Vec<Value> {
+        data.data.iter().map(|row| row[1].clone()).collect()
+    }
+
+    #[test]
+    fn forward_fill_copies_the_last_value() {
+        let mut data = column(&[None, Some(1), None, None, Some(4), None]);
+        assert_eq!(data.fill_missing("x", Strategy::ForwardFill).unwrap(), 3);
+        let filled = [Value::Null, Value::Int(1), Value::Int(1), Value::Int(1)];
+        assert_eq!(values(&data)[..4], filled);
+        assert_eq!(values(&data)[5], Value::Int(4));
+    }
+
+    #[test]
+    fn constant_fills_every_null() {
+        let mut data = column(&[None, Some(1), None]);
+        let zero = Strategy::Constant(Value::Int(0));
+        assert_eq!(data.fill_missing("x", zero).unwrap(), 2);
+        assert_eq!(values(&data), [Value::Int(0), Value::Int(1), Value::Int(0)]);
+    }
+
+    #[test]
+    fn linear_interpolates_between_numbers() {
+        let mut data = column(&[None, Some(1), None, None, Some(7), None, Some(8)]);
+        assert_eq!(data.fill_missing("x", Strategy::Linear).unwrap(), 3);
+        let filled = values(&data);
+        assert_eq!(filled[0], Value::Null);
+        assert_eq!(filled[2..4], [Value::Int(3), Value::Int(5)]);
+        assert_eq!(filled[5], Value::Float(7.5));
+
+        let mut data = column(&[Some(1), None]);
+        data.data[0][1] = Value::Text("a".into());
+        assert!(matches!(
+            data.fill_missing("x", Strategy::Linear),
+            Err(CsvError::TypeMismatch(e)) if e.line_num == 2
+        ));
+    }
+
+    #[test]
+    fn short_records_are_resized() {
+        let mut data = column(&[Some(1), None, Some(3)]);
+        data.data[1].truncate(1);
+        assert_eq!(data.fill_missing("x", Strategy::ForwardFill).unwrap(), 1);
+        assert_eq!(data.data[1], [Value::Int(1), Value::Int(1)]);
+    }
+
+    #[test]
+    fn unknown_column() {
+        let mut data = column(&[None]);
+        assert!(matches!(
+            data.fill_missing("y", Strategy::ForwardFill),
+            Err(CsvError::UnknownColumn(c)) if c == "y"
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+use std::{
+    fmt::Display,
+    hash::Hasher,
+    io::{self, Read},
+    path::Path,
+    time::SystemTime,
+};
+
+use super::{
+    file::open_file, reader::BOM, tokenizer::Scanner, CsvData, CsvError, CsvFileError,
+    CsvReadOptions, Result,
+};
+
+/// What identifies the contents of a file, to tell whether it changed since
+/// it was last processed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub struct Fingerprint {
+    /// Size in bytes.
+    pub len: u64,
+    /// Modification time, if the platform keeps one.
+    pub modified: Option<SystemTime>,
+    /// Hash of the whole file.
+    pub content_hash: u64,
+    /// Hash of the first record, without BOM or line break, to tell a
+    /// change of columns from a change of data.
+    pub header_hash: u64,
+}
+
+impl Fingerprint {
+    /// Whether `self` and `other` were taken of the same contents. The
+    /// modification time doesn't count, so a file that was only touched is
+    /// unchanged.
+    pub fn same_contents(&self, other: &Fingerprint) -> bool {
+        self.len == other.len
+            && self.content_hash == other.content_hash
+            && self.header_hash == other.header_hash
+    }
+}
+
+/// The [`Fingerprint`] of the file at `path`. Its first record is found as
+/// with the default options, `,` and `"`.
+pub fn csv_fingerprint(path: &str) -> Result<Fingerprint> {
+    let (len, modified) = metadata(path)?;
+    let mut file = open_file(path)?;
+    let mut scanner = Scanner::new(&CsvReadOptions::default());
+    let mut content = FastHasher::default();
+    let mut header = Vec::new();
+    let mut in_header = true;
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        // ERROR 3: file could not be read.
+        let read =
+            fill(&mut file, &mut buf).map_err(|e| CsvError::CouldNotParseLine(Box::new(e)))?;
+        if read == 0 {
+            break;
+        }
+        let chunk = &buf[..read];
+        content.write(chunk);
+        if in_header {
+            // Only the first chunk can start with the BOM.
+            let chunk = match header.is_empty() {
+                true => chunk.strip_prefix(BOM).unwrap_or(chunk),
+                false => chunk,
+            };
+            match scanner.record_end(chunk) {
+                Some(end) => {
+                    header.extend_from_slice(&chunk[..end]);
+                    in_header = false;
+                }
+                None => header.extend_from_slice(chunk),
+            }
+        }
+    }
+    let mut header_hash = FastHasher::default();
+    header_hash.write(header.strip_suffix(b"\r").unwrap_or(&header));
+    Ok(Fingerprint {
+        len,
+        modified,
+        content_hash: content.finish(),
+        header_hash: header_hash.finish(),
+    })
+}
+
+impl<T: Display> CsvData<T> {
+    /// A hash of the header and the text of every value, to tell whether two
+    /// tables hold the same data, however they were read.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = FastHasher::default();
+        let mut text = String::new();
+        let mut field = |hasher: &mut FastHasher, value: &dyn Display| {
+            use std::fmt::Write;
+            text.clear();
+            let _ = write!(text, "{}", value);
+            // The length keeps `ab`, `c` apart from `a`, `bc`.
+            hasher.write_usize(text.len());
+            hasher.write(text.as_bytes());
+        };
+        hasher.write_usize(self.header.len());
+        for name in &self.header {
+            field(&mut hasher, name);
+        }
+        for record in &self.data {
+            hasher.write_usize(record.len());
+            for value in record {
+                field(&mut hasher, value);
+            }
+        }
+        hasher.finish()
+    }
+}
+
+/// The size and modification time of `filename`.
+pub(super) fn metadata(filename: &str) -> Result<(u64, Option<SystemTime>)> {
+    let path = Path::new(filename);
+    // ERROR 1: file could be missing or inaccessible.
+    let metadata = path
+        .metadata()
+        .map_err(|e| CsvFileError::into_error(path, e))?;
+    Ok((metadata.len(), metadata.modified().ok()))
+}
+
+/// Reads into `buf` until it is full or the input ends, so that only the
+/// last chunk of a file is short.
+fn fill(input: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// A fast, non-cryptographic hash, taking the input eight bytes at a time.
+/// Good for telling changed contents apart, not against deliberate
+/// collisions.
+#[derive(Default)]
+struct FastHasher {
+    hash: u64,
+    len: u64,
+    /// Bytes not yet mixed in, up to seven, in the low end.
+    tail: u64,
+    tail_len: u32,
+}
+
+impl FastHasher {
+    fn mix(hash: u64, word: u64) -> u64 {
+        (hash.rotate_left(5) ^ word).wrapping_mul(0x517c_c1b7_2722_0a95)
+    }
+}
+
+impl Hasher for FastHasher {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        while self.tail_len > 0 && self.tail_len < 8 {
+            let Some((&b, rest)) = bytes.split_first() else {
+                return;
+            };
+            self.tail |= u64::from(b) << (8 * self.tail_len);
+            self.tail_len += 1;
+            bytes = rest;
+        }
+        if self.tail_len == 8 {
+            self.hash = Self::mix(self.hash, self.tail);
+            (self.tail, self.tail_len) = (0, 0);
+        }
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            let word = u64::from_le_bytes(word.try_into().unwrap_or_default());
+            self.hash = Self::mix(self.hash, word);
+        }
+        for &b in words.remainder() {
+            self.tail |= u64::from(b) << (8 * self.tail_len);
+            self.tail_len += 1;
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hash = self.hash;
+        if self.tail_len > 0 {
+            hash = Self::mix(hash, self.tail);
+        }
+        // Spreads every input bit over the whole hash (MurmurHash3's
+        // finalizer).
+        hash = Self::mix(hash, self.len);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        hash ^ (hash >> 33)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    fn table(header: &[&str], rows: &[&[&str]]) -> CsvData<String> {
+        CsvData {
+            header: header.iter().map(|name| name.to_string()).collect(),
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    fn hash(pieces: &[&[u8]]) -> u64 {
+        let mut hasher = FastHasher::default();
+        for piece in pieces {
+            hasher.write(piece);
+        }
+        hasher.finish()
+    }
+
+    #[test]
+    fn the_hash_does_not_depend_on_how_the_input_is_split() {
+        let input = b"id,name\n1,Ada\n2,Grace\n";
+        let whole = hash(&[input]);
+        for at in 0..input.len() {
+            let (a, b) = input.split_at(at);
+            assert_eq!(hash(&[a, b]), whole, "split at {}", at);
+        }
+        let bytes: Vec<&[u8]> = input.chunks(1).collect();
+        assert_eq!(hash(&bytes), whole);
+        assert_ne!(hash(&[b"id,name\n1,Ada\n2,Grace\r\n"]), whole);
+        // Trailing zero bytes change the length, so the hash.
+        assert_ne!(hash(&[b"a"]), hash(&[b"a\0"]));
+        assert_ne!(hash(&[b""]), hash(&[b"\0"]));
+    }
+
+    #[test]
+    fn content_hash_is_stable_and_tells_tables_apart() {
+        let data = table(&["a", "b"], &[&["1", "2"], &["3", "4"]]);
+        assert_eq!(data.content_hash(), data.content_hash());
+        assert_eq!(
+            data.content_hash(),
+            table(&["a", "b"], &[&["1", "2"], &["3", "4"]]).content_hash()
+        );
+
+        let changed_cell = table(&["a", "b"], &[&["1", "2"], &["3", "5"]]);
+        assert_ne!(data.content_hash(), changed_cell.content_hash());
+        let changed_header = table(&["a", "c"], &[&["1", "2"], &["3", "4"]]);
+        assert_ne!(data.content_hash(), changed_header.content_hash());
+
+        let ab_c = table(&["x", "y"], &[&["ab", "c"]]);
+        let a_bc = table(&["x", "y"], &[&["a", "bc"]]);
+        assert_ne!(ab_c.content_hash(), a_bc.content_hash());
+        // Nor by a value moving from one record to the next.
+        let split = table(&["x"], &[&["a", "b"], &[]]);
+        let moved = table(&["x"], &[&["a"], &["b"]]);
+        assert_ne!(split.content_hash(), moved.content_hash());
+    }
+
+    #[test]
+    fn content_hash_goes_by_the_text_of_values() {
+        let text = table(&["n"], &[&["1"], &["2"]]);
+        let numbers = CsvData {
+            header: vec!["n".to_string()],
+            data: vec![vec![1], vec![2]],
+        };
+        assert_eq!(text.content_hash(), numbers.content_hash());
+    }
+
+    #[test]
+    fn fingerprints_tell_a_change_of_columns_from_a_change_of_data() {
+        let file = TempFile::new("fingerprint.csv", "id,name\n1,Ada\n");
+        let first = csv_fingerprint(file.path()).unwrap();
+        assert_eq!(first.len, 14);
+        assert!(first.same_contents(&csv_fingerprint(file.path()).unwrap()));
+
+        let data = TempFile::new("fingerprint_data.csv", "id,name\n1,Grace\n");
+        let data = csv_fingerprint(data.path()).unwrap();
+        assert!(!first.same_contents(&data));
+        assert_ne!(first.content_hash, data.content_hash);
+        assert_eq!(first.header_hash, data.header_hash);
+
+        let columns = TempFile::new("fingerprint_columns.csv", "id,nom\n1,Ada\n");
+        assert_ne!(
+            first.header_hash,
+            csv_fingerprint(columns.path()).unwrap().header_hash
+        );
+
+        // Neither the BOM nor a CRLF line break is part of the header.
+        let bom = TempFile::new("fingerprint_bom.csv", "\u{feff}id,name\r\n1,Ada\r\n");
+        assert_eq!(
+            first.header_hash,
+            csv_fingerprint(bom.path()).unwrap().header_hash
+        );
+    }
+
+    #[test]
+    fn a_missing_file_cannot_be_fingerprinted() {
+        let file = TempFile::empty("fingerprint_missing.csv");
+        assert!(matches!(
+            csv_fingerprint(file.path()),
+            Err(CsvError::FileNotFound(_))
+        ));
+    }
+}
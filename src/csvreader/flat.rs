@@ -0,0 +1,163 @@
+use alloc::{string::String, vec::Vec};
+
+use super::CsvData;
+
+/// Records stored back to back in one buffer, `header.len()` values each,
+/// instead of in an allocation per record. Read with
+/// [`read_csv_flat`](super::read_csv_flat).
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FlatCsvData<T> {
+    pub header: Vec<String>,
+    pub values: Vec<T>,
+}
+
+impl<T> FlatCsvData<T> {
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.values
+            .len()
+            .checked_div(self.header.len())
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Record number `i`, counting from 0.
+    pub fn row(&self, i: usize) -> Option<&[T]> {
+        let width = self.header.len();
+        (i < self.len()).then(|| &self.values[i * width..(i + 1) * width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.values.chunks_exact(self.header.len().max(1))
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.values.chunks_exact_mut(self.header.len().max(1))
+    }
+}
+
+impl<T> From<FlatCsvData<T>> for CsvData<T> {
+    fn from(flat: FlatCsvData<T>) -> Self {
+        let (len, width) = (flat.len(), flat.header.len());
+        let mut values = flat.values.into_iter();
+        let data = (0..len)
+            .map(|_| values.by_ref().take(width).collect())
+            .collect();
+        CsvData {
+            header: flat.header,
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::*;
+    use crate::csvreader::{parse_csv, parse_csv_flat, CsvReadOptions, OnError, Strictness};
+
+    fn flat(header: &[&str], values: &[&str]) -> FlatCsvData<String> {
+        FlatCsvData {
+            header: header.iter().map(|name| String::from(*name)).collect(),
+            values: values.iter().map(|value| String::from(*value)).collect(),
+        }
+    }
+
+    #[test]
+    fn records_are_sliced_by_the_header_width() {
+        let data = flat(&["a", "b"], &["1", "", "", "4", "", ""]);
+        assert_eq!((data.len(), data.is_empty()), (3, false));
+        assert_eq!(data.row(0).unwrap(), ["1", ""]);
+        assert_eq!(data.row(1).unwrap(), ["", "4"]);
+        assert_eq!(data.row(2).unwrap(), ["", ""]);
+        assert_eq!(data.row(3), None);
+        assert_eq!(data.rows().count(), 3);
+
+        let data = CsvData::from(data);
+        assert_eq!(data.data, [["1", ""], ["", "4"], ["", ""]]);
+    }
+
+    #[test]
+    fn no_records_or_no_columns_is_empty() {
+        let data = flat(&["a", "b"], &[]);
+        assert!(data.is_empty());
+        assert_eq!(data.row(0), None);
+        assert_eq!(data.rows().count(), 0);
+        assert!(CsvData::from(data).data.is_empty());
+
+        let mut data = flat(&[], &[]);
+        assert_eq!(data.len(), 0);
+        assert_eq!(data.rows_mut().count(), 0);
+        assert!(CsvData::from(data).data.is_empty());
+    }
+
+    #[test]
+    fn rows_can_be_changed_in_place() {
+        let mut data = flat(&["a", "b"], &["1", "2", "3", "4"]);
+        for row in data.rows_mut() {
+            row.swap(0, 1);
+        }
+        assert_eq!(data.values, ["2", "1", "4", "3"]);
+    }
+
+    /// Reads `input` flat and not, which must agree, records and report.
+    fn both(input: &str, options: &CsvReadOptions) -> CsvData<String> {
+        let records = parse_csv::<String>(input.as_bytes(), options);
+        let flat = parse_csv_flat::<String>(input.as_bytes(), options);
+        match (records, flat) {
+            (Ok((records, report)), Ok((flat, flat_report))) => {
+                assert_eq!(format!("{:?}", report), format!("{:?}", flat_report));
+                let flat = CsvData::from(flat);
+                assert_eq!(records.header, flat.header);
+                assert_eq!(records.data, flat.data);
+                records
+            }
+            (records, flat) => panic!("{:?} and {:?}", records, flat),
+        }
+    }
+
+    #[test]
+    fn flat_reads_agree_with_reads_per_record() {
+        let options = CsvReadOptions::default();
+        let data = both("a,b\n1,\n,\n\"\",4\n", &options);
+        assert_eq!(data.data, [["1", ""], ["", ""], ["", "4"]]);
+        assert!(both("a,b\n", &options).data.is_empty());
+
+        let options = CsvReadOptions::default()
+            .strictness(Strictness::Warn)
+            .pad_short_lines(true)
+            .truncate_long_lines(true);
+        let data = both("a,b\n1\n2,3,4\n", &options);
+        assert_eq!(data.data, [["1", ""], ["2", "3"]]);
+
+        let options = CsvReadOptions::default().on_error(OnError::Skip);
+        let data = both("a,b\n1\n2,3\n4,5,6\n", &options);
+        assert_eq!(data.data, [["2", "3"]]);
+
+        let options = CsvReadOptions::default().has_header(false);
+        assert_eq!(both("1,2\n3,4\n", &options).data, [["1", "2"], ["3", "4"]]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn files_read_flat_agree_with_reads_per_record() {
+        use crate::csvreader::{
+            read_csv_flat_with_options, read_csv_with_options, testutil::TempFile,
+        };
+
+        let file = TempFile::new("flat.csv", "a,b\r\n1,\r\n\"x\ny\",2\r\n3\r\n");
+        let options = CsvReadOptions::default().on_error(OnError::Skip);
+        let (records, report) = read_csv_with_options::<String>(file.path(), &options).unwrap();
+        let (flat, flat_report) =
+            read_csv_flat_with_options::<String>(file.path(), &options).unwrap();
+        assert_eq!(format!("{:?}", report), format!("{:?}", flat_report));
+        assert_eq!(flat.header, ["a", "b"]);
+        assert_eq!(CsvData::from(flat).data, records.data);
+        assert_eq!(records.data, [["1", ""], ["x\ny", "2"]]);
+    }
+}
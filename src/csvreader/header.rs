@@ -0,0 +1,301 @@
+use alloc::{format, string::String, vec, vec::Vec};
+
+use super::{CsvError, CsvHeaderDiff, DuplicateHeaders, Result};
+
+/// Compares the header of a file against the expected column names. With
+/// `any_order`, columns only have to be present.
+pub(super) fn check_header(header: &[String], expected: &[String], any_order: bool) -> Result<()> {
+    let missing: Vec<String> = expected
+        .iter()
+        .filter(|name| !header.contains(name))
+        .cloned()
+        .collect();
+    let unexpected: Vec<String> = header
+        .iter()
+        .filter(|name| !expected.contains(name))
+        .cloned()
+        .collect();
+
+    // Columns present on both sides, compared in their relative order.
+    let common: Vec<&String> = header
+        .iter()
+        .filter(|name| expected.contains(name))
+        .collect();
+    let mut reordered: Vec<String> = expected
+        .iter()
+        .filter(|name| header.contains(name))
+        .zip(common)
+        .filter(|(want, got)| want != got)
+        .map(|(want, _)| want.clone())
+        .collect();
+
+    if any_order {
+        reordered.clear();
+    }
+
+    if missing.is_empty() && unexpected.is_empty() && reordered.is_empty() {
+        return Ok(());
+    }
+    Err(CsvError::HeaderMismatch(CsvHeaderDiff {
+        missing,
+        unexpected,
+        reordered,
+    }))
+}
+
+/// Joins the names on each line of a multi-line header into one name per
+/// column, see [`CsvReadOptions::header_rows`](super::CsvReadOptions::header_rows).
+pub(super) fn join_header_rows(mut rows: Vec<Vec<String>>, separator: &str) -> Vec<String> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let groups = rows.len().saturating_sub(1);
+    // Empty group names continue the group to their left.
+    for row in &mut rows[..groups] {
+        row.resize(width, String::new());
+        for i in 1..width {
+            if row[i].is_empty() {
+                row[i] = row[i - 1].clone();
+            }
+        }
+    }
+    (0..width)
+        .map(|i| {
+            let parts: Vec<&str> = rows
+                .iter()
+                .filter_map(|row| row.get(i))
+                .map(String::as_str)
+                .filter(|name| !name.is_empty())
+                .collect();
+            parts.join(separator)
+        })
+        .collect()
+}
+
+/// Applies the duplicate column policy. Returns the resulting header and,
+/// for each field of a record, whether it is kept.
+pub(super) fn dedup_header(
+    header: Vec<String>,
+    policy: DuplicateHeaders,
+) -> Result<(Vec<String>, Vec<bool>)> {
+    let mut keep = vec![true; header.len()];
+    let mut duplicates: Vec<String> = Vec::new();
+    for (i, name) in header.iter().enumerate() {
+        if header[..i].contains(name) {
+            keep[i] = false;
+            if !duplicates.contains(name) {
+                duplicates.push(name.clone());
+            }
+        }
+    }
+    if duplicates.is_empty() {
+        return Ok((header, keep));
+    }
+
+    match policy {
+        DuplicateHeaders::Error => Err(CsvError::DuplicateHeader(duplicates)),
+        DuplicateHeaders::KeepFirst => {
+            let header = header
+                .into_iter()
+                .zip(&keep)
+                .filter(|(_, &keep)| keep)
+                .map(|(name, _)| name)
+                .collect();
+            Ok((header, keep))
+        }
+        DuplicateHeaders::AutoSuffix => {
+            let mut renamed: Vec<String> = Vec::with_capacity(header.len());
+            for name in &header {
+                let mut unique = name.clone();
+                let mut n = 1;
+                while renamed.contains(&unique) || (n > 1 && header.contains(&unique)) {
+                    n += 1;
+                    unique = format!("{}_{}", name, n);
+                }
+                renamed.push(unique);
+            }
+            Ok((renamed, vec![true; header.len()]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::{parse_csv, CsvData, CsvReadOptions};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| String::from(*name)).collect()
+    }
+
+    #[test]
+    fn empty_group_names_continue_the_group_to_their_left() {
+        let rows = vec![
+            names(&["", "Q1", "", "Q2", ""]),
+            names(&["id", "jan", "feb", "jan", "feb"]),
+        ];
+        assert_eq!(
+            join_header_rows(rows, "_"),
+            ["id", "Q1_jan", "Q1_feb", "Q2_jan", "Q2_feb"]
+        );
+    }
+
+    #[test]
+    fn rows_of_different_lengths_are_joined_to_the_widest() {
+        // A short group row continues its last name, a short last row
+        // leaves the names of the groups.
+        let rows = vec![
+            names(&["top"]),
+            names(&["a", "", "b", ""]),
+            names(&["x", "", "z"]),
+        ];
+        assert_eq!(
+            join_header_rows(rows, "."),
+            ["top.a.x", "top.a", "top.b.z", "top.b"]
+        );
+    }
+
+    #[test]
+    fn columns_named_on_no_row_stay_empty() {
+        let rows = vec![names(&["", "g"]), names(&["", "v", ""])];
+        assert_eq!(join_header_rows(rows, "_"), ["", "g_v", "g"]);
+        assert_eq!(join_header_rows(vec![names(&["a", ""])], "_"), ["a", ""]);
+        assert!(join_header_rows(Vec::new(), "_").is_empty());
+    }
+
+    #[test]
+    fn renames_apply_to_the_header_as_it_is_read() {
+        let options = CsvReadOptions::new().rename(&[("Cust ID", "id"), ("nope", "x")]);
+        let (data, _): (CsvData<String>, _) =
+            parse_csv(b"Cust ID,name\n1,Ada\n", &options).unwrap();
+        // Renaming a column the file doesn't have does nothing.
+        assert_eq!(data.header, ["id", "name"]);
+        assert_eq!(data.data, [["1", "Ada"]]);
+    }
+
+    #[test]
+    fn a_rename_to_an_existing_name_is_a_duplicate() {
+        let input = b"a,b\n1,2\n";
+        let options = CsvReadOptions::new().rename(&[("a", "b")]);
+        match parse_csv::<String>(input, &options) {
+            Err(CsvError::DuplicateHeader(names)) => assert_eq!(names, ["b"]),
+            other => panic!("expected a duplicate header, got {:?}", other),
+        }
+        let options = options.duplicate_headers(DuplicateHeaders::AutoSuffix);
+        let (data, _) = parse_csv::<String>(input, &options).unwrap();
+        assert_eq!(data.header, ["b", "b_2"]);
+
+        // Swapping names doesn't collide.
+        let options = CsvReadOptions::new().rename(&[("a", "b"), ("b", "a")]);
+        let (data, _) = parse_csv::<String>(input, &options).unwrap();
+        assert_eq!(data.header, ["b", "a"]);
+    }
+
+    #[test]
+    fn auto_suffix_numbers_later_duplicates() {
+        let (header, keep) = dedup_header(
+            names(&["id", "a", "id", "id"]),
+            DuplicateHeaders::AutoSuffix,
+        )
+        .unwrap();
+        assert_eq!(header, ["id", "a", "id_2", "id_3"]);
+        assert_eq!(keep, [true; 4]);
+    }
+
+    #[test]
+    fn auto_suffix_skips_names_the_header_has() {
+        let (header, _) =
+            dedup_header(names(&["a", "a", "a_2"]), DuplicateHeaders::AutoSuffix).unwrap();
+        assert_eq!(header, ["a", "a_3", "a_2"]);
+        let (header, _) =
+            dedup_header(names(&["a", "a_2", "a"]), DuplicateHeaders::AutoSuffix).unwrap();
+        assert_eq!(header, ["a", "a_2", "a_3"]);
+    }
+
+    #[test]
+    fn keep_first_drops_later_duplicates_and_their_fields() {
+        let (header, keep) = dedup_header(
+            names(&["a", "b", "a", "b", "c"]),
+            DuplicateHeaders::KeepFirst,
+        )
+        .unwrap();
+        assert_eq!(header, ["a", "b", "c"]);
+        assert_eq!(keep, [true, true, false, false, true]);
+
+        let (data, _) = parse_csv::<String>(
+            b"a,b,a\n1,2,3\n",
+            &CsvReadOptions::new().duplicate_headers(DuplicateHeaders::KeepFirst),
+        )
+        .unwrap();
+        assert_eq!(data.header, ["a", "b"]);
+        assert_eq!(data.data, [["1", "2"]]);
+    }
+
+    #[test]
+    fn the_error_policy_lists_each_duplicate_once() {
+        match dedup_header(names(&["b", "a", "b", "a", "b"]), DuplicateHeaders::Error) {
+            Err(CsvError::DuplicateHeader(duplicates)) => assert_eq!(duplicates, ["b", "a"]),
+            other => panic!("expected duplicates, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_header_without_duplicates_is_kept_by_any_policy() {
+        for policy in [
+            DuplicateHeaders::Error,
+            DuplicateHeaders::AutoSuffix,
+            DuplicateHeaders::KeepFirst,
+        ] {
+            let (header, keep) = dedup_header(names(&["a", "a_2", "b"]), policy).unwrap();
+            assert_eq!(header, ["a", "a_2", "b"]);
+            assert_eq!(keep, [true; 3]);
+        }
+    }
+
+    fn diff(header: &[&str], expected: &[&str], any_order: bool) -> CsvHeaderDiff {
+        match check_header(&names(header), &names(expected), any_order) {
+            Err(CsvError::HeaderMismatch(diff)) => diff,
+            other => panic!("expected a header mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn matching_headers_pass() {
+        assert!(check_header(&names(&["a", "b"]), &names(&["a", "b"]), false).is_ok());
+        assert!(check_header(&names(&["b", "a"]), &names(&["a", "b"]), true).is_ok());
+        assert!(check_header(&[], &[], false).is_ok());
+    }
+
+    #[test]
+    fn missing_and_extra_columns_are_listed() {
+        let missing = diff(&["a"], &["a", "b", "c"], false);
+        assert_eq!(missing.missing, ["b", "c"]);
+        assert!(missing.unexpected.is_empty() && missing.reordered.is_empty());
+
+        let extra = diff(&["x", "a", "b"], &["a", "b"], true);
+        assert_eq!(extra.unexpected, ["x"]);
+        assert!(extra.missing.is_empty() && extra.reordered.is_empty());
+    }
+
+    #[test]
+    fn reordered_columns_are_listed_unless_any_order_goes() {
+        let reordered = diff(&["b", "a", "c"], &["a", "b", "c"], false);
+        assert_eq!(reordered.reordered, ["a", "b"]);
+        assert!(reordered.missing.is_empty() && reordered.unexpected.is_empty());
+
+        // Only the columns both have are compared for order.
+        let all = diff(&["c", "x", "a"], &["a", "b", "c"], false);
+        assert_eq!(all.missing, ["b"]);
+        assert_eq!(all.unexpected, ["x"]);
+        assert_eq!(all.reordered, ["a", "c"]);
+        let any_order = diff(&["c", "x", "a"], &["a", "b", "c"], true);
+        assert!(any_order.reordered.is_empty());
+    }
+
+    #[test]
+    fn the_expected_header_is_checked_before_any_record() {
+        let options = CsvReadOptions::new().expect_header(&["id", "name"]);
+        match parse_csv::<i64>(b"name,id\nAda,x\n", &options) {
+            Err(CsvError::HeaderMismatch(diff)) => assert_eq!(diff.reordered, ["id", "name"]),
+            other => panic!("expected a header mismatch, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::{
+    stream_csv, ColumnSchema, ColumnType, CsvReadOptions, CsvSchema, OnError, Result, Strictness,
+    Value,
+};
+
+/// Guesses a schema from the header and first `sample_rows` records of
+/// `filename`: each column gets the narrowest type all its values fit, and
+/// is nullable if it had empty cells.
+///
+/// Records that don't have the header's length are left out of the sample.
+pub fn infer_schema(filename: &str, sample_rows: usize) -> Result<CsvSchema> {
+    infer_schema_with_options(filename, sample_rows, &CsvReadOptions::new())
+}
+
+/// Like [`infer_schema`], for a file laid out as `options` describe. The
+/// strictness is always lenient.
+pub fn infer_schema_with_options(
+    filename: &str,
+    sample_rows: usize,
+    options: &CsvReadOptions,
+) -> Result<CsvSchema> {
+    infer(filename, sample_rows, options, false)
+}
+
+/// Like [`infer_schema_with_options`], but numeric columns also get the
+/// range of the sample as `min`/`max` constraints. Values outside it that
+/// the sample didn't reach fail a check against the schema, even in the
+/// same file.
+pub fn infer_schema_with_ranges(
+    filename: &str,
+    sample_rows: usize,
+    options: &CsvReadOptions,
+) -> Result<CsvSchema> {
+    infer(filename, sample_rows, options, true)
+}
+
+fn infer(
+    filename: &str,
+    sample_rows: usize,
+    options: &CsvReadOptions,
+    ranges: bool,
+) -> Result<CsvSchema> {
+    // Only the sample is read. The stream reads just far enough ahead to
+    // tell its last records from the footer, which only matters when the
+    // sample reaches the end of the file.
+    let mut options = options
+        .clone()
+        .strictness(Strictness::Lenient)
+        .on_error(OnError::Skip);
+    options.max_errors = None;
+    let mut records = stream_csv::<Value>(filename, &options)?;
+    let header = records.header().to_vec();
+    let sample = records
+        .by_ref()
+        .take(sample_rows)
+        .collect::<Result<Vec<_>>>()?;
+
+    let columns = header.iter().enumerate().map(|(i, name)| {
+        let values: Vec<&Value> = sample.iter().filter_map(|row| row.get(i)).collect();
+        let column_type = values
+            .iter()
+            .filter_map(|v| v.column_type())
+            .reduce(ColumnType::widen)
+            .unwrap_or(ColumnType::Text);
+        let mut column = ColumnSchema::new(name, column_type);
+        if values.iter().any(|v| v.is_null()) {
+            column = column.nullable();
+        }
+        if ranges && matches!(column_type, ColumnType::Int | ColumnType::Float) {
+            let numbers = values.iter().filter_map(|v| v.as_f64());
+            let min = numbers.clone().reduce(f64::min);
+            let max = numbers.reduce(f64::max);
+            if let (Some(min), Some(max)) = (min, max) {
+                column = column.min(min).max(max);
+            }
+        }
+        column
+    });
+    Ok(columns.fold(CsvSchema::new(), CsvSchema::column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::{read_csv_validated_with_schema, testutil::TempFile};
+
+    /// Values that grow past the sample, as in a time series.
+    fn growing(name: &str) -> TempFile {
+        let mut contents = String::from("id,reading\n");
+        for i in 0..1500 {
+            contents.push_str(&format!("{},{}.5\n", i, i * 2));
+        }
+        TempFile::new(name, &contents)
+    }
+
+    #[test]
+    fn inferred_schema_accepts_the_file_it_was_inferred_from() {
+        let file = growing("same-file.csv");
+        let options = CsvReadOptions::new();
+        let schema = infer_schema_with_options(file.path(), 1000, &options).unwrap();
+        assert!(schema.columns.iter().all(|c| c.constraints.is_empty()));
+
+        let (data, report) =
+            read_csv_validated_with_schema(file.path(), &schema, &options).unwrap();
+        assert!(!report.has_errors(), "{:?}", report.errors);
+        assert_eq!(data.data.len(), 1500);
+    }
+
+    #[test]
+    fn ranges_are_those_of_the_sample() {
+        let file = growing("ranges.csv");
+        let options = CsvReadOptions::new();
+        let schema = infer_schema_with_ranges(file.path(), 1000, &options).unwrap();
+        assert_eq!(schema.columns[0].column_type, ColumnType::Int);
+        assert_eq!(schema.columns[1].column_type, ColumnType::Float);
+
+        let (_, report) = read_csv_validated_with_schema(file.path(), &schema, &options).unwrap();
+        assert_eq!(report.errors.len(), 2 * 500);
+    }
+
+    #[test]
+    fn footer_and_short_records_are_left_out_of_the_sample() {
+        let file = TempFile::new("footer.csv", "a,b\n1,x\n2,y\n3\n4,\nTotal,9\n");
+        let options = CsvReadOptions::new().skip_footer(1);
+        let schema = infer_schema_with_options(file.path(), 10, &options).unwrap();
+        assert_eq!(schema.columns[0].column_type, ColumnType::Int);
+        assert!(schema.columns[1].nullable);
+    }
+}
@@ -0,0 +1,107 @@
+use std::{cell::RefCell, collections::HashSet, sync::Arc};
+
+use super::{
+    read, CsvData, CsvErrorReport, CsvReadOptions, FieldParser, FromStrParser, Layout, Result,
+};
+
+/// Reads `filename` as text, keeping one copy of each distinct value: every
+/// cell holding the same text shares the same `Arc<str>`. For columns with
+/// few distinct values, such as a country, this takes a fraction of the
+/// memory of a `CsvData<String>`.
+pub fn read_csv_interned(filename: &str) -> Result<CsvData<Arc<str>>> {
+    read_csv_interned_with_options(filename, &CsvReadOptions::default()).map(|(data, _)| data)
+}
+
+/// [`read_csv_interned`] with explicit options, see
+/// [`read_csv_with_options`](super::read_csv_with_options).
+pub fn read_csv_interned_with_options(
+    filename: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<Arc<str>>, CsvErrorReport)> {
+    read(filename, options, false, &mut Interner::default())
+}
+
+/// [`read_csv_interned`] in collect-all mode, see
+/// [`read_csv_validated`](super::read_csv_validated).
+pub fn read_csv_interned_validated(
+    filename: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<Arc<str>>, CsvErrorReport)> {
+    read(filename, options, true, &mut Interner::default())
+}
+
+/// Hands out a shared copy of each value, from a pool of those seen so far.
+#[derive(Default)]
+struct Interner {
+    pool: RefCell<HashSet<Arc<str>>>,
+}
+
+impl FieldParser<Arc<str>> for Interner {
+    fn layout(&mut self, header: Vec<String>, options: &CsvReadOptions) -> Result<Layout> {
+        FieldParser::<String>::layout(&mut FromStrParser, header, options)
+    }
+
+    fn parse(&self, text: &str, _line_num: usize, _column: usize) -> Result<Arc<str>> {
+        let mut pool = self.pool.borrow_mut();
+        if let Some(value) = pool.get(text) {
+            return Ok(Arc::clone(value));
+        }
+        let value: Arc<str> = text.into();
+        pool.insert(Arc::clone(&value));
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::{read_csv, testutil::TempFile, OnError};
+
+    const COUNTRIES: &str = "name,country,born\nAda,UK,UK\nGrace,US,US\n\"Kat\",\"US\",UK\n,,\n";
+
+    #[test]
+    fn values_read_as_with_read_csv() {
+        let file = TempFile::new("interned.csv", COUNTRIES);
+        let interned = read_csv_interned(file.path()).unwrap();
+        let text: CsvData<String> = read_csv(file.path()).unwrap();
+        assert_eq!(interned.header, text.header);
+        let interned: Vec<Vec<&str>> = interned
+            .data
+            .iter()
+            .map(|record| record.iter().map(|value| &**value).collect())
+            .collect();
+        assert_eq!(interned, text.data);
+    }
+
+    #[test]
+    fn the_same_text_shares_one_value() {
+        let file = TempFile::new("interned_shared.csv", COUNTRIES);
+        let data = read_csv_interned(file.path()).unwrap();
+        let [ada, grace, kat, empty] = &data.data[..] else {
+            panic!("expected four records, got {:?}", data.data);
+        };
+        // Across columns and records, quoted or not.
+        assert!(Arc::ptr_eq(&ada[1], &ada[2]));
+        assert!(Arc::ptr_eq(&grace[1], &kat[1]));
+        assert!(Arc::ptr_eq(&ada[1], &kat[2]));
+        assert!(!Arc::ptr_eq(&ada[1], &grace[1]));
+        assert!(Arc::ptr_eq(&empty[0], &empty[2]));
+        // The pool is gone with the read, leaving the cells.
+        assert_eq!(Arc::strong_count(&ada[1]), 3);
+    }
+
+    #[test]
+    fn problems_are_handled_as_with_read_csv() {
+        let file = TempFile::new("interned_short.csv", "a,b\nx,y\nx\nx,y\n");
+        let options = CsvReadOptions::default().on_error(OnError::Skip);
+        let (data, report) = read_csv_interned_with_options(file.path(), &options).unwrap();
+        assert_eq!(data.data.len(), 2);
+        assert_eq!(report.skipped.len(), 1);
+
+        let (data, report) =
+            read_csv_interned_validated(file.path(), &CsvReadOptions::default()).unwrap();
+        assert_eq!(data.data.len(), 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line_num(), Some(3));
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::{CsvData, CsvError, Result};
+
+/// Which records of the left table a [`CsvData::join`] keeps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Only records with a match on the right.
+    #[default]
+    Inner,
+    /// Every record, with default values where there is no match.
+    Left,
+}
+
+impl<T: Clone + Default + Display> CsvData<T> {
+    /// Joins `right` onto `self` where the two `on` columns are equal.
+    ///
+    /// The result has the columns of `self` followed by those of `right`
+    /// other than `on`, with `_right` appended to names `self` already has.
+    /// A record matching several on the right appears once per match, and
+    /// keys are compared by their text, a missing one being empty.
+    pub fn join(&self, right: &CsvData<T>, on: &str, kind: JoinKind) -> Result<CsvData<T>> {
+        let position = |data: &CsvData<T>| {
+            data.header
+                .iter()
+                .position(|name| name == on)
+                .ok_or_else(|| CsvError::UnknownColumn(on.into()))
+        };
+        let (left_key, right_key) = (position(self)?, position(right)?);
+
+        let mut matches: HashMap<String, Vec<&Vec<T>>> = HashMap::new();
+        for row in &right.data {
+            matches.entry(key(row, right_key)).or_default().push(row);
+        }
+
+        let mut header = self.header.clone();
+        for (i, name) in right.header.iter().enumerate() {
+            if i == right_key {
+                continue;
+            }
+            match header.contains(name) {
+                true => header.push(format!("{}_right", name)),
+                false => header.push(name.clone()),
+            }
+        }
+
+        let widths = (self.header.len(), right.header.len());
+        let mut data = Vec::new();
+        for row in &self.data {
+            match matches.get(&key(row, left_key)) {
+                Some(found) => {
+                    data.extend(found.iter().map(|r| merge(row, Some(r), right_key, widths)))
+                }
+                None if kind == JoinKind::Left => data.push(merge(row, None, right_key, widths)),
+                None => {}
+            }
+        }
+        Ok(CsvData { header, data })
+    }
+}
+
+/// The text of the key in column `i` of `row`, empty if the record is too
+/// short to have it.
+fn key<T: Display>(row: &[T], i: usize) -> String {
+    row.get(i).map(ToString::to_string).unwrap_or_default()
+}
+
+/// `left` followed by the fields of `right` other than its key, or
+/// defaults for a missing match or fields short records don't have.
+fn merge<T: Clone + Default>(
+    left: &[T],
+    right: Option<&Vec<T>>,
+    right_key: usize,
+    (left_width, right_width): (usize, usize),
+) -> Vec<T> {
+    let mut row = left.to_vec();
+    if row.len() < left_width {
+        row.resize(left_width, T::default());
+    }
+    for i in (0..right_width).filter(|&i| i != right_key) {
+        row.push(
+            right
+                .and_then(|right| right.get(i))
+                .cloned()
+                .unwrap_or_default(),
+        );
+    }
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(header: &[&str], rows: &[&[&str]]) -> CsvData<String> {
+        CsvData {
+            header: header.iter().map(|name| name.to_string()).collect(),
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn joins_on_a_column() {
+        let left = table(&["id", "name"], &[&["1", "a"], &["2", "b"], &["3", "c"]]);
+        let right = table(&["name", "id"], &[&["x", "1"], &["y", "1"], &["z", "3"]]);
+        let joined = left.join(&right, "id", JoinKind::Inner).unwrap();
+        assert_eq!(joined.header, ["id", "name", "name_right"]);
+        assert_eq!(
+            joined.data,
+            [["1", "a", "x"], ["1", "a", "y"], ["3", "c", "z"]]
+        );
+        let joined = left.join(&right, "id", JoinKind::Left).unwrap();
+        assert_eq!(joined.data[2], ["2", "b", ""]);
+        assert!(matches!(
+            left.join(&right, "key", JoinKind::Inner),
+            Err(CsvError::UnknownColumn(_))
+        ));
+    }
+
+    #[test]
+    fn short_records_have_empty_fields() {
+        let left = table(&["id", "name", "note"], &[&["1", "a"], &[]]);
+        let right = table(&["id", "qty", "price"], &[&["1", "5"], &[""]]);
+        let joined = left.join(&right, "id", JoinKind::Inner).unwrap();
+        assert_eq!(joined.data, [["1", "a", "", "5", ""], ["", "", "", "", ""]]);
+    }
+}
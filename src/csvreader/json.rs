@@ -0,0 +1,358 @@
+use std::{fmt, str::FromStr};
+
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use super::{
+    file::read_bytes,
+    reader::BOM,
+    rows::{parse_rows, row_line},
+    CsvData, CsvError, CsvErrorReport, CsvReadOptions, FieldParser, FromStrParser, Result,
+};
+
+/// Reads a JSON Lines file, one object per line, as [`read_csv`](super::read_csv)
+/// reads a CSV file. The keys of the first object name the columns, in the
+/// order they are written; nested objects are flattened into columns named
+/// `parent.child`, and arrays kept as their JSON text. Every value is
+/// parsed from its text, `null` as an empty field.
+///
+/// An object without one of the columns has an empty field for it; one
+/// with keys the first doesn't have is too long, as a CSV record with too
+/// many fields would be. Problems are reported with the line of the object
+/// as their line number; blank lines are skipped.
+pub fn read_jsonl<T: Default + FromStr>(path: &str) -> Result<CsvData<T>> {
+    read_jsonl_with_options(path, &CsvReadOptions::default()).map(|(data, _)| data)
+}
+
+/// [`read_jsonl`] with explicit options, see
+/// [`read_csv_with_options`](super::read_csv_with_options). The delimiter,
+/// quote and header options don't apply.
+pub fn read_jsonl_with_options<T: Default + FromStr>(
+    path: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    read(path, options, false, &mut FromStrParser)
+}
+
+/// [`read_jsonl`] in collect-all mode, see
+/// [`read_csv_validated`](super::read_csv_validated). A line that isn't a
+/// JSON object still stops the read.
+pub fn read_jsonl_validated<T: Default + FromStr>(
+    path: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    read(path, options, true, &mut FromStrParser)
+}
+
+fn read<T: Default>(
+    path: &str,
+    options: &CsvReadOptions,
+    collect_errors: bool,
+    parser: &mut dyn FieldParser<T>,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let bytes = read_bytes(path)?;
+    let bytes = bytes.strip_prefix(BOM).unwrap_or(&bytes);
+    let mut objects = Vec::new();
+    for (i, line) in bytes.split(|&b| b == b'\n').enumerate() {
+        if line.trim_ascii().is_empty() {
+            continue;
+        }
+        // ERROR 3: line is not a JSON object.
+        let object = match serde_json::from_slice(line) {
+            Ok(Json::Object(fields)) => fields,
+            Ok(_) => return Err(not_an_object(i + 1)),
+            Err(e) => {
+                return Err(CsvError::CouldNotParseLine(
+                    format!("line {}: {}", i + 1, e).into(),
+                ))
+            }
+        };
+        objects.push((i + 1, object));
+    }
+    parse_objects(objects, options, collect_errors, parser)
+}
+
+impl<T: Default + FromStr> CsvData<T> {
+    /// Reads `value`, an array of objects, as [`read_jsonl`] reads a file.
+    /// Problems are reported with the 1-based index of the object as their
+    /// line number. Columns are in the order of the keys of the first
+    /// object, as `serde_json` keeps them: sorted, unless its
+    /// `preserve_order` feature is on.
+    pub fn from_json(value: &serde_json::Value) -> Result<Self> {
+        Self::from_json_with_options(value, &CsvReadOptions::default()).map(|(data, _)| data)
+    }
+
+    /// [`from_json`](CsvData::from_json) with explicit options, see
+    /// [`read_jsonl_with_options`].
+    pub fn from_json_with_options(
+        value: &serde_json::Value,
+        options: &CsvReadOptions,
+    ) -> Result<(Self, CsvErrorReport)> {
+        // ERROR 3: value is not an array of objects.
+        let serde_json::Value::Array(values) = value else {
+            return Err(CsvError::CouldNotParseLine(
+                "expected an array of JSON objects".into(),
+            ));
+        };
+        let objects = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| match Json::from(value) {
+                Json::Object(fields) => Ok((i + 1, fields)),
+                _ => Err(not_an_object(i + 1)),
+            })
+            .collect::<Result<_>>()?;
+        parse_objects(objects, options, false, &mut FromStrParser)
+    }
+}
+
+fn not_an_object(line_num: usize) -> CsvError {
+    CsvError::CouldNotParseLine(format!("line {}: not a JSON object", line_num).into())
+}
+
+/// Parses `objects`, each with its line number, as the records of a file
+/// whose header is the keys of the first.
+fn parse_objects<T: Default>(
+    objects: Vec<(usize, Vec<(String, Json)>)>,
+    options: &CsvReadOptions,
+    collect_errors: bool,
+    parser: &mut dyn FieldParser<T>,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let mut line_nums = Vec::with_capacity(objects.len());
+    let mut lines = Vec::with_capacity(objects.len() + 1);
+    let mut header: Vec<String> = Vec::new();
+    for (i, (line_num, object)) in objects.into_iter().enumerate() {
+        let mut fields = Vec::new();
+        flatten("", object, &mut fields);
+        if i == 0 {
+            header = fields.iter().map(|(name, _)| name.clone()).collect();
+            lines.push(row_line(&header));
+        }
+        line_nums.push(line_num);
+        lines.push(row_line(in_header_order(&header, fields)));
+    }
+    let options = options.clone().has_header(true).header_rows(1);
+    // The header is named by the first object, so it shares its line.
+    let line_num = |i: usize| line_nums[i.saturating_sub(1)];
+    parse_rows(&lines, line_num, &options, collect_errors, parser)
+}
+
+/// The values of `fields` in the order of `header`, empty for the columns
+/// they don't have, followed by those of the names `header` doesn't have.
+fn in_header_order(header: &[String], fields: Vec<(String, String)>) -> Vec<String> {
+    // Objects usually have the keys of the first, in the same order.
+    if fields.len() == header.len() && fields.iter().zip(header).all(|((n, _), h)| n == h) {
+        return fields.into_iter().map(|(_, value)| value).collect();
+    }
+    let mut values = vec![String::new(); header.len()];
+    let mut extra = Vec::new();
+    for (name, value) in fields {
+        match header.iter().position(|h| *h == name) {
+            Some(i) => values[i] = value,
+            None => extra.push(value),
+        }
+    }
+    values.extend(extra);
+    values
+}
+
+/// Appends the fields of `object` to `fields`, as names and texts, with
+/// nested objects flattened.
+fn flatten(prefix: &str, object: Vec<(String, Json)>, fields: &mut Vec<(String, String)>) {
+    for (key, value) in object {
+        let name = match prefix {
+            "" => key,
+            prefix => format!("{}.{}", prefix, key),
+        };
+        match value {
+            Json::Object(object) if !object.is_empty() => flatten(&name, object, fields),
+            Json::Object(_) => fields.push((name, "{}".into())),
+            Json::Text(text) => fields.push((name, text)),
+        }
+    }
+}
+
+/// A JSON value with the keys of its objects in the order they are written
+/// and anything else as the text of its field.
+enum Json {
+    Object(Vec<(String, Json)>),
+    Text(String),
+}
+
+impl From<&serde_json::Value> for Json {
+    fn from(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Object(map) => Json::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), Json::from(value)))
+                    .collect(),
+            ),
+            serde_json::Value::Null => Json::Text(String::new()),
+            serde_json::Value::String(text) => Json::Text(text.clone()),
+            value => Json::Text(value.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
+
+struct JsonVisitor;
+
+impl<'de> Visitor<'de> for JsonVisitor {
+    type Value = Json;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> core::result::Result<Json, E> {
+        Ok(Json::Text(String::new()))
+    }
+
+    fn visit_bool<E>(self, b: bool) -> core::result::Result<Json, E> {
+        Ok(Json::Text(b.to_string()))
+    }
+
+    fn visit_i64<E>(self, i: i64) -> core::result::Result<Json, E> {
+        Ok(Json::Text(i.to_string()))
+    }
+
+    fn visit_u64<E>(self, u: u64) -> core::result::Result<Json, E> {
+        Ok(Json::Text(u.to_string()))
+    }
+
+    fn visit_f64<E>(self, x: f64) -> core::result::Result<Json, E> {
+        // As `serde_json` writes it, keeping the `.0` of a whole number.
+        Ok(Json::Text(serde_json::Value::from(x).to_string()))
+    }
+
+    fn visit_str<E>(self, text: &str) -> core::result::Result<Json, E> {
+        Ok(Json::Text(text.into()))
+    }
+
+    fn visit_string<E>(self, text: String) -> core::result::Result<Json, E> {
+        Ok(Json::Text(text))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> core::result::Result<Json, A::Error> {
+        let mut items: Vec<serde_json::Value> = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Json::Text(serde_json::Value::Array(items).to_string()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> core::result::Result<Json, A::Error> {
+        let mut fields = Vec::new();
+        while let Some(field) = map.next_entry()? {
+            fields.push(field);
+        }
+        Ok(Json::Object(fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn objects_become_records_with_nested_keys_flattened() {
+        let file = TempFile::new(
+            "json_records.jsonl",
+            "{\"id\":1,\"name\":{\"first\":\"Ada\",\"last\":\"L\"},\"tags\":[\"x\",2],\"score\":1.0,\"ok\":true}\n\
+             \n\
+             {\"id\":2,\"name\":{\"first\":\"Bo\"},\"tags\":[],\"score\":null,\"ok\":false}\n",
+        );
+        let data: CsvData<String> = read_jsonl(file.path()).unwrap();
+        assert_eq!(
+            data.header,
+            ["id", "name.first", "name.last", "tags", "score", "ok"]
+        );
+        assert_eq!(
+            data.data,
+            rows(&[
+                &["1", "Ada", "L", "[\"x\",2]", "1.0", "true"],
+                &["2", "Bo", "", "[]", "", "false"],
+            ])
+        );
+    }
+
+    #[test]
+    fn escapes_are_decoded_and_arrays_kept_as_json() {
+        let file = TempFile::new(
+            "json_escapes.jsonl",
+            "{\"a \\\"b\\\"\":\"x,\\\"y\\\"\\n\\u00e9\",\"list\":[\"q\\\"\",{\"k\":null}]}\n",
+        );
+        let data: CsvData<String> = read_jsonl(file.path()).unwrap();
+        assert_eq!(data.header, ["a \"b\"", "list"]);
+        assert_eq!(
+            data.data,
+            rows(&[&["x,\"y\"\n\u{e9}", "[\"q\\\"\",{\"k\":null}]"]])
+        );
+    }
+
+    #[test]
+    fn arrays_of_objects_are_read_like_lines() {
+        let value = json!([{ "a": "1", "b": 2 }, { "b": "3" }, { "a": "4", "b": "5", "c": "6" }]);
+        let options = CsvReadOptions::default().truncate_long_lines(true);
+        let (data, _) = CsvData::<String>::from_json_with_options(&value, &options).unwrap();
+        assert_eq!(data.header, ["a", "b"]);
+        assert_eq!(data.data, rows(&[&["1", "2"], &["", "3"], &["4", "5"]]));
+
+        // Extra keys make the record too long; its line is the object's index.
+        match CsvData::<String>::from_json(&value) {
+            Err(CsvError::RecordLengthMismatch(e)) => {
+                assert_eq!((e.line_num, e.expected, e.num_entries), (3, 2, 3))
+            }
+            other => panic!("expected a length mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_data_is_an_empty_file() {
+        let file = TempFile::new("json_empty.jsonl", "\n \n");
+        assert!(matches!(
+            read_jsonl::<String>(file.path()),
+            Err(CsvError::FileIsEmpty)
+        ));
+        assert!(matches!(
+            CsvData::<String>::from_json(&json!([])),
+            Err(CsvError::FileIsEmpty)
+        ));
+    }
+
+    #[test]
+    fn values_that_are_not_objects_are_rejected_with_their_line() {
+        let message = |result: Result<CsvData<String>>| match result {
+            Err(CsvError::CouldNotParseLine(e)) => e.to_string(),
+            other => panic!("expected a parse error, got {:?}", other),
+        };
+        let file = TempFile::new("json_not_object.jsonl", "{\"a\":1}\n[1]\n");
+        assert_eq!(
+            message(read_jsonl(file.path())),
+            "line 2: not a JSON object"
+        );
+        let file = TempFile::new("json_invalid.jsonl", "{\"a\":1}\n{\"a\":\n");
+        assert!(message(read_jsonl(file.path())).starts_with("line 2: EOF"));
+        assert_eq!(
+            message(CsvData::from_json(&json!({ "a": 1 }))),
+            "expected an array of JSON objects"
+        );
+        assert_eq!(
+            message(CsvData::from_json(&json!([{ "a": 1 }, 2]))),
+            "line 2: not a JSON object"
+        );
+    }
+}
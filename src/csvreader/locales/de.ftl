@@ -0,0 +1,32 @@
+file_not_found = Datei nicht gefunden: { $path }
+permission_denied = Zugriff verweigert: { $path }
+is_a_directory = Ist ein Verzeichnis: { $path }
+could_not_open_file = { $path } konnte nicht geöffnet werden: { $reason }
+could_not_write_file = { $path } konnte nicht geschrieben werden: { $reason }
+could_not_parse_line = Zeile konnte nicht gelesen werden: { $reason }
+file_is_empty = Datei ist leer
+could_not_parse_value = Zeile { $line }, Feld { $field }: Wert { $value } konnte nicht gelesen werden
+record_length_mismatch = Zeile { $line }: { $expected } Felder erwartet, { $found } gefunden: { $raw }
+record_length_mismatch_at = Zeile { $line }: { $expected } Felder erwartet, { $found } gefunden (unerwartetes Trennzeichen bei Byte { $boundary }): { $raw }
+byte_order_mark = Datei beginnt mit einer Byte-Order-Markierung
+invalid_utf8 = Zeile { $line }: ungültiges UTF-8
+trailing_delimiter = Zeile { $line }: Trennzeichen am Zeilenende
+empty_value = Zeile { $line }, Feld { $field }: leerer Wert
+header_mismatch = Kopfzeile stimmt nicht überein
+header_mismatch_missing = fehlend: { $names }
+header_mismatch_unexpected = unerwartet: { $names }
+header_mismatch_reordered = umsortiert: { $names }
+duplicate_header = doppelte Spaltennamen: { $names }
+type_mismatch = Zeile { $line }, Spalte { $name }: { $type } erwartet, { $value } gefunden
+not_nullable = Zeile { $line }, Spalte { $name }: Wert fehlt
+constraint_violation = Zeile { $line }, Spalte { $name }: { $value } verletzt { $constraint }
+duplicate_key = Zeile { $line }: Schlüssel { $columns } = { $values } bereits in Zeile { $first } verwendet
+rule_violation = Zeile { $line }: verletzt Regel { $rule }
+invalid_config = ungültige Konfiguration { $path }: { $reason }
+unknown_column = Spalte nicht gefunden: { $column }
+invalid_filter = ungültiger Filter { $filter } bei Zeichen { $position }: { $reason }
+unknown_sheet = Tabellenblatt nicht gefunden: { $sheet }
+query_failed = Abfrage { $query } fehlgeschlagen: { $reason }
+map_failed = Zeile { $line }: { $reason }
+map_failed_column = Zeile { $line }, Spalte { $name }: { $reason }
+in_file = in { $path }
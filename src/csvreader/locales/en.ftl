@@ -0,0 +1,33 @@
+# Message templates for CsvError, see `messages.rs` for the arguments.
+file_not_found = file not found: { $path }
+permission_denied = permission denied: { $path }
+is_a_directory = is a directory: { $path }
+could_not_open_file = could not open { $path }: { $reason }
+could_not_write_file = could not write { $path }: { $reason }
+could_not_parse_line = could not read line: { $reason }
+file_is_empty = file is empty
+could_not_parse_value = line { $line }, field { $field }: could not parse value { $value }
+record_length_mismatch = line { $line }: expected { $expected } fields, found { $found }: { $raw }
+record_length_mismatch_at = line { $line }: expected { $expected } fields, found { $found } (unexpected delimiter at byte { $boundary }): { $raw }
+byte_order_mark = file starts with a byte order mark
+invalid_utf8 = line { $line }: invalid UTF-8
+trailing_delimiter = line { $line }: trailing delimiter
+empty_value = line { $line }, field { $field }: empty value
+header_mismatch = header mismatch
+header_mismatch_missing = missing: { $names }
+header_mismatch_unexpected = unexpected: { $names }
+header_mismatch_reordered = reordered: { $names }
+duplicate_header = duplicate column names: { $names }
+type_mismatch = line { $line }, column { $name }: expected { $type }, found { $value }
+not_nullable = line { $line }, column { $name }: missing value
+constraint_violation = line { $line }, column { $name }: { $value } violates { $constraint }
+duplicate_key = line { $line }: key { $columns } = { $values } already used on line { $first }
+rule_violation = line { $line }: fails rule { $rule }
+invalid_config = invalid configuration { $path }: { $reason }
+unknown_column = no such column: { $column }
+invalid_filter = invalid filter { $filter } at character { $position }: { $reason }
+unknown_sheet = no such sheet: { $sheet }
+query_failed = query { $query } failed: { $reason }
+map_failed = line { $line }: { $reason }
+map_failed_column = line { $line }, column { $name }: { $reason }
+in_file = in { $path }
@@ -0,0 +1,32 @@
+file_not_found = fichier introuvable : { $path }
+permission_denied = permission refusée : { $path }
+is_a_directory = est un répertoire : { $path }
+could_not_open_file = impossible d’ouvrir { $path } : { $reason }
+could_not_write_file = impossible d’écrire { $path } : { $reason }
+could_not_parse_line = impossible de lire la ligne : { $reason }
+file_is_empty = le fichier est vide
+could_not_parse_value = ligne { $line }, champ { $field } : impossible d’analyser la valeur { $value }
+record_length_mismatch = ligne { $line } : { $expected } champs attendus, { $found } trouvés : { $raw }
+record_length_mismatch_at = ligne { $line } : { $expected } champs attendus, { $found } trouvés (séparateur inattendu à l’octet { $boundary }) : { $raw }
+byte_order_mark = le fichier commence par une marque d’ordre des octets
+invalid_utf8 = ligne { $line } : UTF-8 invalide
+trailing_delimiter = ligne { $line } : séparateur en fin de ligne
+empty_value = ligne { $line }, champ { $field } : valeur vide
+header_mismatch = en-tête non conforme
+header_mismatch_missing = manquantes : { $names }
+header_mismatch_unexpected = inattendues : { $names }
+header_mismatch_reordered = réordonnées : { $names }
+duplicate_header = noms de colonnes en double : { $names }
+type_mismatch = ligne { $line }, colonne { $name } : { $type } attendu, { $value } trouvé
+not_nullable = ligne { $line }, colonne { $name } : valeur manquante
+constraint_violation = ligne { $line }, colonne { $name } : { $value } ne respecte pas { $constraint }
+duplicate_key = ligne { $line } : clé { $columns } = { $values } déjà utilisée à la ligne { $first }
+rule_violation = ligne { $line } : ne respecte pas la règle { $rule }
+invalid_config = configuration invalide { $path } : { $reason }
+unknown_column = colonne introuvable : { $column }
+invalid_filter = filtre invalide { $filter } au caractère { $position } : { $reason }
+unknown_sheet = feuille introuvable : { $sheet }
+query_failed = échec de la requête { $query } : { $reason }
+map_failed = ligne { $line } : { $reason }
+map_failed_column = ligne { $line }, colonne { $name } : { $reason }
+in_file = dans { $path }
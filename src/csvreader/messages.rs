@@ -0,0 +1,280 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+use super::CsvError;
+
+/// One localizable piece of an error message: a template key plus the
+/// arguments substituted into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    fn new(key: &'static str, args: Vec<(&'static str, String)>) -> Self {
+        Self { key, args }
+    }
+}
+
+/// Source of message templates. Templates refer to arguments Fluent-style,
+/// as `{ $name }`.
+pub trait MessageCatalog {
+    fn template(&self, key: &str) -> Option<&str>;
+}
+
+/// A catalog read from `key = template` lines, a small subset of the Fluent
+/// `.ftl` format. Blank lines and `#` comments are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    templates: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    pub fn parse(source: &str) -> Self {
+        let templates = entries(source)
+            .map(|(key, template)| (key.into(), template.into()))
+            .collect();
+        Self { templates }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn english() -> &'static Self {
+        static ENGLISH: OnceLock<Catalog> = OnceLock::new();
+        ENGLISH.get_or_init(|| Self::parse(include_str!("locales/en.ftl")))
+    }
+
+    pub fn german() -> Self {
+        Self::parse(include_str!("locales/de.ftl"))
+    }
+
+    pub fn french() -> Self {
+        Self::parse(include_str!("locales/fr.ftl"))
+    }
+}
+
+impl MessageCatalog for Catalog {
+    fn template(&self, key: &str) -> Option<&str> {
+        self.templates.get(key).map(String::as_str)
+    }
+}
+
+/// The built-in English templates, looked up without building a
+/// [`Catalog`], so they are available without `std`.
+pub(super) struct English;
+
+impl MessageCatalog for English {
+    fn template(&self, key: &str) -> Option<&str> {
+        entries(include_str!("locales/en.ftl"))
+            .find_map(|(k, template)| (k == key).then_some(template))
+    }
+}
+
+/// The `key = template` lines of `source`.
+fn entries(source: &str) -> impl Iterator<Item = (&str, &str)> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, template)| (key.trim(), template.trim()))
+}
+
+impl CsvError {
+    /// The pieces this error's message is made of, joined by `", "` when
+    /// rendered.
+    pub fn messages(&self) -> Vec<Message> {
+        let line = |line_num: &usize| ("line", line_num.to_string());
+        match self {
+            #[cfg(feature = "std")]
+            Self::FileNotFound(path) | Self::PermissionDenied(path) | Self::IsADirectory(path) => {
+                vec![Message::new(
+                    self.code(),
+                    vec![("path", path.display().to_string())],
+                )]
+            }
+            #[cfg(feature = "std")]
+            Self::InFile(e) => {
+                let path = e.path.display().to_string();
+                let mut messages = vec![Message::new("in_file", vec![("path", path)])];
+                messages.extend(e.error.messages());
+                messages
+            }
+            #[cfg(feature = "std")]
+            Self::CouldNotOpenFile(e) | Self::CouldNotWriteFile(e) => vec![Message::new(
+                self.code(),
+                vec![
+                    ("path", e.path.display().to_string()),
+                    ("reason", e.source.to_string()),
+                ],
+            )],
+            #[cfg(feature = "std")]
+            Self::InvalidConfig(e) => vec![Message::new(
+                self.code(),
+                vec![
+                    ("path", e.path.display().to_string()),
+                    ("reason", e.reason.clone()),
+                ],
+            )],
+            Self::InvalidFilter(e) => vec![Message::new(
+                self.code(),
+                vec![
+                    ("filter", format!("{:?}", e.filter)),
+                    (
+                        "position",
+                        (e.filter[..e.offset].chars().count() + 1).to_string(),
+                    ),
+                    ("reason", e.reason.clone()),
+                ],
+            )],
+            #[cfg(feature = "std")]
+            Self::CouldNotParseLine(e) => {
+                vec![Message::new(self.code(), vec![("reason", e.to_string())])]
+            }
+            Self::FileIsEmpty | Self::ByteOrderMark => vec![Message::new(self.code(), vec![])],
+            Self::CouldNotParseValue(pos) | Self::EmptyValue(pos) => vec![Message::new(
+                self.code(),
+                vec![
+                    line(&pos.line_num),
+                    ("field", (pos.column + 1).to_string()),
+                    ("value", format!("{:?}", pos.value)),
+                ],
+            )],
+            Self::RecordLengthMismatch(len) => {
+                let mut args = vec![
+                    line(&len.line_num),
+                    ("expected", len.expected.to_string()),
+                    ("found", len.num_entries.to_string()),
+                    ("raw", format!("{:?}", len.raw)),
+                ];
+                let key = match len.boundary {
+                    Some(boundary) => {
+                        args.push(("boundary", boundary.to_string()));
+                        "record_length_mismatch_at"
+                    }
+                    None => "record_length_mismatch",
+                };
+                vec![Message::new(key, args)]
+            }
+            Self::InvalidUtf8(line_num) | Self::TrailingDelimiter(line_num) => {
+                vec![Message::new(self.code(), vec![line(line_num)])]
+            }
+            Self::HeaderMismatch(diff) => {
+                let mut messages = vec![Message::new(self.code(), vec![])];
+                for (key, names) in [
+                    ("header_mismatch_missing", &diff.missing),
+                    ("header_mismatch_unexpected", &diff.unexpected),
+                    ("header_mismatch_reordered", &diff.reordered),
+                ] {
+                    if !names.is_empty() {
+                        messages.push(Message::new(key, vec![("names", names.join(", "))]));
+                    }
+                }
+                messages
+            }
+            Self::TypeMismatch(e) | Self::NotNullable(e) => vec![Message::new(
+                self.code(),
+                vec![
+                    line(&e.line_num),
+                    ("name", e.name.clone()),
+                    ("type", e.expected.to_string()),
+                    ("value", format!("{:?}", e.value)),
+                ],
+            )],
+            Self::ConstraintViolation(e) => vec![Message::new(
+                self.code(),
+                vec![
+                    line(&e.line_num),
+                    ("name", e.name.clone()),
+                    ("value", format!("{:?}", e.value)),
+                    ("constraint", e.constraint.to_string()),
+                ],
+            )],
+            Self::DuplicateKey(e) => vec![Message::new(
+                self.code(),
+                vec![
+                    line(&e.line_num),
+                    ("columns", e.columns.join(", ")),
+                    ("values", format!("{:?}", e.values.join(", "))),
+                    ("first", e.first_line.to_string()),
+                ],
+            )],
+            Self::RuleViolation(e) => vec![Message::new(
+                self.code(),
+                vec![line(&e.line_num), ("rule", e.rule.clone())],
+            )],
+            Self::UnknownColumn(column) => {
+                vec![Message::new(self.code(), vec![("column", column.clone())])]
+            }
+            Self::UnknownSheet(sheet) => {
+                vec![Message::new(self.code(), vec![("sheet", sheet.clone())])]
+            }
+            Self::QueryFailed(e) => vec![Message::new(
+                self.code(),
+                vec![
+                    ("query", format!("{:?}", e.query)),
+                    ("reason", e.reason.clone()),
+                ],
+            )],
+            Self::MapFailed(e) => {
+                let mut args = vec![line(&e.line_num), ("reason", e.reason.clone())];
+                let key = match &e.column {
+                    Some((_, name)) => {
+                        args.push(("name", name.clone()));
+                        "map_failed_column"
+                    }
+                    None => "map_failed",
+                };
+                vec![Message::new(key, args)]
+            }
+            Self::DuplicateHeader(names) => {
+                vec![Message::new(self.code(), vec![("names", names.join(", "))])]
+            }
+        }
+    }
+
+    /// Renders the message with templates from `catalog`, falling back to
+    /// English for keys it doesn't have.
+    pub fn localize(&self, catalog: &dyn MessageCatalog) -> String {
+        self.messages()
+            .iter()
+            .map(|message| {
+                let template = catalog
+                    .template(message.key)
+                    .or_else(|| English.template(message.key))
+                    .unwrap_or(message.key);
+                render(template, &message.args)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Substitutes `{ $name }` placeholders. Unknown placeholders are kept.
+fn render(template: &str, args: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out += &rest[..start];
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[start..start + end + 1];
+        let name = placeholder[1..placeholder.len() - 1]
+            .trim()
+            .trim_start_matches('$');
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => out += value,
+            None => out += placeholder,
+        }
+        rest = &rest[start + end + 1..];
+    }
+    out + rest
+}
@@ -0,0 +1,167 @@
+use std::{
+    num::NonZeroUsize,
+    panic,
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use super::{
+    header, read, CsvData, CsvError, CsvErrorReport, CsvInFileError, CsvReadOptions, FromStrParser,
+    Result,
+};
+
+/// Reads `filenames` in parallel, on at most one thread per core, and joins
+/// their records in the order of `filenames`. Every file must have the
+/// header of the first. Problems are handled according to `options`, like
+/// in [`read_csv_with_options`](super::read_csv_with_options); the reports
+/// are returned in the order of `filenames` too.
+///
+/// If any file fails, so does the whole read, with a
+/// [`CsvError::InFile`] naming the first file that failed.
+pub fn read_csv_files<T: Default + FromStr + Send>(
+    filenames: &[&str],
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, Vec<CsvErrorReport>)> {
+    read_files(filenames, options, false)
+}
+
+/// [`read_csv_files`] in collect-all mode, see
+/// [`read_csv_validated`](super::read_csv_validated).
+pub fn read_csv_files_validated<T: Default + FromStr + Send>(
+    filenames: &[&str],
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, Vec<CsvErrorReport>)> {
+    read_files(filenames, options, true)
+}
+
+fn read_files<T: Default + FromStr + Send>(
+    filenames: &[&str],
+    options: &CsvReadOptions,
+    collect_errors: bool,
+) -> Result<(CsvData<T>, Vec<CsvErrorReport>)> {
+    let threads = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(filenames.len());
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<_> = filenames.iter().map(|_| None).collect();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    // Each worker takes the next file nobody has started on.
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(filename) = filenames.get(i) else {
+                            break done;
+                        };
+                        let result = read(filename, options, collect_errors, &mut FromStrParser);
+                        done.push((i, result));
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            let done = worker.join().unwrap_or_else(|e| panic::resume_unwind(e));
+            for (i, result) in done {
+                results[i] = Some(result);
+            }
+        }
+    });
+
+    let mut data = CsvData {
+        header: Vec::new(),
+        data: Vec::new(),
+    };
+    let mut reports = Vec::with_capacity(filenames.len());
+    for (i, (filename, result)) in filenames.iter().zip(results).enumerate() {
+        let attribute = |error| in_file(filename, error);
+        let (file, report) = result.expect("every file is read").map_err(attribute)?;
+        if i == 0 {
+            data.header = file.header;
+        } else {
+            // ERROR 8: header is not the one of the first file.
+            header::check_header(&file.header, &data.header, false).map_err(attribute)?;
+        }
+        data.data.extend(file.data);
+        reports.push(report);
+    }
+    Ok((data, reports))
+}
+
+/// `error`, tied to `filename` unless it already names a file.
+fn in_file(filename: &str, error: CsvError) -> CsvError {
+    if error.path().is_some() {
+        return error;
+    }
+    CsvError::InFile(CsvInFileError {
+        path: Path::new(filename).to_path_buf(),
+        error: Box::new(error),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    #[test]
+    fn records_are_joined_in_the_order_of_the_files() {
+        let files: Vec<TempFile> = (0..5)
+            .map(|i| {
+                TempFile::new(
+                    "multi.csv",
+                    &format!("id,n\n{},a\n{},b\n", i * 2, i * 2 + 1),
+                )
+            })
+            .collect();
+        let paths: Vec<&str> = files.iter().map(TempFile::path).collect();
+        let (data, reports) = read_csv_files::<String>(&paths, &CsvReadOptions::default()).unwrap();
+        assert_eq!(data.header, ["id", "n"]);
+        let ids: Vec<&str> = data.data.iter().map(|record| record[0].as_str()).collect();
+        assert_eq!(ids, ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]);
+        assert_eq!(reports.len(), 5);
+        assert!(reports.iter().all(|report| report.records == 2));
+    }
+
+    #[test]
+    fn a_mismatched_header_names_its_file() {
+        let first = TempFile::new("multi_first.csv", "id,n\n1,a\n");
+        let second = TempFile::new("multi_second.csv", "id,m\n2,b\n");
+        let paths = [first.path(), second.path()];
+        match read_csv_files::<String>(&paths, &CsvReadOptions::default()) {
+            Err(CsvError::InFile(e)) => {
+                assert_eq!(e.path, Path::new(second.path()));
+                match *e.error {
+                    CsvError::HeaderMismatch(diff) => {
+                        assert_eq!(diff.missing, ["n"]);
+                        assert_eq!(diff.unexpected, ["m"]);
+                    }
+                    other => panic!("expected a header mismatch, got {:?}", other),
+                }
+            }
+            other => panic!("expected an error in a file, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn errors_that_name_their_file_are_not_wrapped() {
+        let first = TempFile::new("multi_present.csv", "id\n1\n");
+        let missing = TempFile::empty("multi_missing.csv");
+        let paths = [first.path(), missing.path()];
+        match read_csv_files::<String>(&paths, &CsvReadOptions::default()) {
+            Err(CsvError::FileNotFound(path)) => assert_eq!(path, Path::new(missing.path())),
+            other => panic!("expected a missing file, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn no_files_read_as_no_records() {
+        let (data, reports) = read_csv_files::<String>(&[], &CsvReadOptions::default()).unwrap();
+        assert!(data.header.is_empty());
+        assert!(data.data.is_empty());
+        assert!(reports.is_empty());
+    }
+}
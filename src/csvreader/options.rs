@@ -0,0 +1,423 @@
+use alloc::{format, string::String, vec::Vec};
+use core::{fmt, str::FromStr};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "std")]
+use super::CsvError;
+
+/// How recoverable problems (see [`Severity::Warning`](super::Severity)) are treated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Strictness {
+    /// Warnings are treated like errors.
+    #[default]
+    Strict,
+    /// Warnings are recovered from and collected in the report.
+    Warn,
+    /// Warnings are recovered from silently.
+    Lenient,
+}
+
+/// What happens to a record with an unrecoverable problem.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum OnError {
+    /// The whole read fails.
+    #[default]
+    Abort,
+    /// The record is left out and listed in the report's `skipped` records.
+    Skip,
+}
+
+/// What happens when the header names the same column more than once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum DuplicateHeaders {
+    /// The read fails.
+    #[default]
+    Error,
+    /// Later occurrences get a numeric suffix: `id`, `id_2`, `id_3`.
+    AutoSuffix,
+    /// Later occurrences are dropped, along with their fields.
+    KeepFirst,
+}
+
+/// Picks columns by name, by 1-based position, or by a range of positions
+/// such as `2-4`, or `3-` for the third column and all after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnSelector {
+    Name(String),
+    /// 0-based.
+    Index(usize),
+    /// 0-based and inclusive, `None` for up to the last column.
+    Range(usize, Option<usize>),
+}
+
+impl ColumnSelector {
+    /// Parses a comma-separated list, e.g. `id,name,5-7`.
+    pub fn parse_list(list: &str) -> Result<Vec<Self>, String> {
+        list.split(',').map(str::parse).collect()
+    }
+}
+
+/// The form [`ColumnSelector::from_str`] parses, positions 1-based.
+impl fmt::Display for ColumnSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => f.write_str(name),
+            Self::Index(i) => write!(f, "{}", i + 1),
+            Self::Range(from, Some(to)) => write!(f, "{}-{}", from + 1, to + 1),
+            Self::Range(from, None) => write!(f, "{}-", from + 1),
+        }
+    }
+}
+
+/// Text that is a number is a position, anything else a name.
+impl FromStr for ColumnSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let position = |n: &str| match n.parse::<usize>() {
+            Ok(0) | Err(_) => Err(format!("invalid column position {:?}", n)),
+            Ok(n) => Ok(n - 1),
+        };
+        if s.is_empty() {
+            return Err("empty column selector".into());
+        }
+        if s.bytes().all(|b| b.is_ascii_digit()) {
+            return position(s).map(Self::Index);
+        }
+        match s.split_once('-') {
+            Some((from, to)) if !from.is_empty() && from.bytes().all(|b| b.is_ascii_digit()) => {
+                let to = match to {
+                    "" => None,
+                    to => Some(position(to)?),
+                };
+                Ok(Self::Range(position(from)?, to))
+            }
+            _ => Ok(Self::Name(s.into())),
+        }
+    }
+}
+
+/// Where a problem passed to an [`ErrorHook`] was found.
+#[derive(Debug)]
+pub struct RecordContext<'a> {
+    pub line_num: usize,
+    pub raw: &'a str,
+    /// Empty for problems with the header itself.
+    pub header: &'a [String],
+}
+
+/// What an [`ErrorHook`] wants done about a problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Handle it as configured by `strictness` and `on_error`.
+    UsePolicy,
+    /// Leave the record out and list it in the report's `skipped` records.
+    Skip,
+    /// Parse this text instead of the offending field. For problems that are
+    /// not tied to a field this is the same as `UsePolicy`.
+    Fix(String),
+    /// Fail the whole read with this problem.
+    Abort,
+}
+
+#[cfg(feature = "std")]
+type ErrorCallback = dyn FnMut(&CsvError, &RecordContext) -> ErrorAction + Send;
+
+/// A callback consulted for every problem found while reading.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct ErrorHook(Arc<Mutex<ErrorCallback>>);
+
+#[cfg(feature = "std")]
+impl ErrorHook {
+    pub fn new(f: impl FnMut(&CsvError, &RecordContext) -> ErrorAction + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(f)))
+    }
+
+    pub(super) fn call(&self, e: &CsvError, ctx: &RecordContext) -> ErrorAction {
+        let mut f = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(e, ctx)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for ErrorHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorHook")
+    }
+}
+
+#[cfg(feature = "std")]
+type FooterCallback = dyn Fn(&str) -> bool + Send + Sync;
+
+/// A test for lines of a footer, see [`CsvReadOptions::footer_when`].
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct FooterHook(Arc<FooterCallback>);
+
+#[cfg(feature = "std")]
+impl FooterHook {
+    pub fn new(f: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for FooterHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FooterHook")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvReadOptions {
+    /// Separates the fields of a record, `,` by default.
+    pub delimiter: char,
+    /// Starts a quoted field, which may hold delimiters, line breaks and the
+    /// quote itself, doubled. `"` by default; `None` reads quotes as text.
+    pub quote: Option<char>,
+    /// Whether the first line names the columns. Without one the columns are
+    /// named `column_1`, `column_2`, ... and line 1 is the first record.
+    pub has_header: bool,
+    /// Number of lines the header takes, 1 by default. With more, the names
+    /// on each line are joined with `header_separator`, the lines above the
+    /// last naming groups of columns: see
+    /// [`header_rows`](CsvReadOptions::header_rows).
+    pub header_rows: usize,
+    pub header_separator: String,
+    /// Column names to replace, as `(name in the file, name to use)` pairs.
+    /// Applied to the header as soon as it is read, so everything that goes
+    /// by name, such as `expected_header`, `columns` and schemas, uses the
+    /// new names. Names the header doesn't have are ignored.
+    pub rename: Vec<(String, String)>,
+    /// Field texts read as empty, such as `NA` or `-`.
+    pub null_values: Vec<String>,
+    pub strictness: Strictness,
+    pub on_error: OnError,
+    /// Fill missing trailing fields with `T::default()` instead of failing.
+    pub pad_short_lines: bool,
+    /// Drop fields beyond the header width instead of failing.
+    pub truncate_long_lines: bool,
+    /// Stop reading once this many problems have been collected.
+    pub max_errors: Option<usize>,
+    /// Fail before parsing any record unless the header is exactly this.
+    pub expected_header: Option<Vec<String>>,
+    pub duplicate_headers: DuplicateHeaders,
+    /// Only read these columns, in this order; the others are not parsed.
+    /// Used by the plain readers, schema and record readers pick their
+    /// columns themselves.
+    pub columns: Option<Vec<ColumnSelector>>,
+    /// Number of records at the end of the file to ignore, such as a totals
+    /// line.
+    pub skip_footer: usize,
+    /// Ignores the records at the end of the file, before the last
+    /// `skip_footer`, for which this holds.
+    #[cfg(feature = "std")]
+    pub footer: Option<FooterHook>,
+    #[cfg(feature = "std")]
+    pub error_hook: Option<ErrorHook>,
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: Some('"'),
+            has_header: true,
+            header_rows: 1,
+            header_separator: "_".into(),
+            rename: Vec::new(),
+            null_values: Vec::new(),
+            strictness: Strictness::default(),
+            on_error: OnError::default(),
+            pad_short_lines: false,
+            truncate_long_lines: false,
+            max_errors: None,
+            expected_header: None,
+            duplicate_headers: DuplicateHeaders::default(),
+            columns: None,
+            skip_footer: 0,
+            #[cfg(feature = "std")]
+            footer: None,
+            #[cfg(feature = "std")]
+            error_hook: None,
+        }
+    }
+}
+
+impl CsvReadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quote(mut self, quote: Option<char>) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Reads a header of `rows` lines, such as group names above field
+    /// names. A column's name joins its names on every line, skipping empty
+    /// ones. An empty name on any but the last line continues the group to
+    /// its left, as merged cells are exported, so that
+    ///
+    /// ```text
+    /// id,Q1,,Q2,
+    /// ,jan,feb,jan,feb
+    /// ```
+    ///
+    /// gives the columns `id`, `Q1_jan`, `Q1_feb`, `Q2_jan` and `Q2_feb`.
+    /// Only used with `has_header`.
+    pub fn header_rows(mut self, rows: usize) -> Self {
+        self.header_rows = rows;
+        self
+    }
+
+    /// Joins the names of a multi-line header, `_` by default.
+    pub fn header_separator(mut self, separator: &str) -> Self {
+        self.header_separator = separator.into();
+        self
+    }
+
+    /// Number of lines before the first record.
+    pub(super) fn header_lines(&self) -> usize {
+        match self.has_header {
+            true => self.header_rows.max(1),
+            false => 0,
+        }
+    }
+
+    /// Renames columns, e.g. `&[("Cust ID", "customer_id")]`; see
+    /// [`rename`](CsvReadOptions::rename).
+    pub fn rename(mut self, names: &[(&str, &str)]) -> Self {
+        self.rename = names
+            .iter()
+            .map(|&(from, to)| (from.into(), to.into()))
+            .collect();
+        self
+    }
+
+    /// The name to use for the column called `name` in the file.
+    pub(super) fn renamed(&self, name: String) -> String {
+        match self.rename.iter().find(|(from, _)| *from == name) {
+            Some((_, to)) => to.clone(),
+            None => name,
+        }
+    }
+
+    pub fn null_values(mut self, values: &[&str]) -> Self {
+        self.null_values = values.iter().map(|&v| v.into()).collect();
+        self
+    }
+
+    /// Whether `text` stands for an empty field.
+    pub(super) fn is_null(&self, text: &str) -> bool {
+        self.null_values.iter().any(|v| v == text)
+    }
+
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    pub fn on_error(mut self, on_error: OnError) -> Self {
+        self.on_error = on_error;
+        self
+    }
+
+    pub fn pad_short_lines(mut self, pad: bool) -> Self {
+        self.pad_short_lines = pad;
+        self
+    }
+
+    pub fn truncate_long_lines(mut self, truncate: bool) -> Self {
+        self.truncate_long_lines = truncate;
+        self
+    }
+
+    pub fn max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = Some(max_errors);
+        self
+    }
+
+    pub fn expect_header(mut self, expected: &[&str]) -> Self {
+        self.expected_header = Some(expected.iter().map(|&name| name.into()).collect());
+        self
+    }
+
+    pub fn duplicate_headers(mut self, policy: DuplicateHeaders) -> Self {
+        self.duplicate_headers = policy;
+        self
+    }
+
+    pub fn select(mut self, columns: Vec<ColumnSelector>) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// Ignores the last `records` records of the file, such as a
+    /// `TOTAL,,,1234.56` line.
+    pub fn skip_footer(mut self, records: usize) -> Self {
+        self.skip_footer = records;
+        self
+    }
+
+    /// Ignores the records at the end of the file for which `f` holds, given
+    /// their text, such as a free-text footer. Checked from the last record
+    /// backwards after dropping `skip_footer` records, until a record `f`
+    /// doesn't hold for.
+    #[cfg(feature = "std")]
+    pub fn footer_when(mut self, f: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.footer = Some(FooterHook::new(f));
+        self
+    }
+
+    /// Whether `raw` is a footer line by the `footer` test.
+    pub(super) fn is_footer(&self, raw: &[u8]) -> bool {
+        #[cfg(feature = "std")]
+        if let Some(FooterHook(f)) = &self.footer {
+            return f(&String::from_utf8_lossy(raw));
+        }
+        let _ = raw;
+        false
+    }
+
+    /// Registers a callback deciding per problem whether to skip the record,
+    /// fix the value or abort, instead of the fixed policies.
+    #[cfg(feature = "std")]
+    pub fn on_problem(
+        mut self,
+        f: impl FnMut(&CsvError, &RecordContext) -> ErrorAction + Send + 'static,
+    ) -> Self {
+        self.error_hook = Some(ErrorHook::new(f));
+        self
+    }
+}
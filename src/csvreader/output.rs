@@ -0,0 +1,261 @@
+use std::{
+    ffi::OsString,
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{CsvData, CsvError, CsvFileError, CsvWriter, Result};
+
+/// Numbers the temporary files of atomic writes running at the same time.
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+/// How [`write_csv_with_options`] and [`write_file`] write a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    pub delimiter: char,
+    /// Write to a temporary file next to the target, sync it to disk and
+    /// rename it over the target, so that the target is never left half
+    /// written: readers see either the old or the whole new file. The new
+    /// file gets the permissions of the old one.
+    pub atomic: bool,
+    /// Keep the previous version of the target as `<target>.bak`,
+    /// replacing an older backup.
+    pub backup: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            atomic: false,
+            backup: false,
+        }
+    }
+}
+
+impl WriteOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    pub fn backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        self
+    }
+}
+
+/// Writes `data` to the file at `path` with a [`CsvWriter`], replacing it
+/// if it exists.
+pub fn write_csv<T: Display>(data: &CsvData<T>, path: &str) -> Result<()> {
+    write_csv_with_options(data, path, &WriteOptions::default())
+}
+
+/// [`write_csv`] with explicit options, such as an atomic write.
+pub fn write_csv_with_options<T: Display>(
+    data: &CsvData<T>,
+    path: &str,
+    options: &WriteOptions,
+) -> Result<()> {
+    write_file(path, options, |out| {
+        CsvWriter::new(out)
+            .delimiter(options.delimiter)
+            .write_data(data)
+    })
+}
+
+/// Creates the file at `path` and has `write` fill it, atomically and with
+/// a backup if `options` say so, for output in other formats such as
+/// [`write_markdown`](super::write_markdown). The delimiter doesn't apply.
+/// If `write` fails, an atomic write leaves the target as it was.
+pub fn write_file(
+    path: &str,
+    options: &WriteOptions,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> Result<()> {
+    let path = Path::new(path);
+    if !options.atomic {
+        if options.backup {
+            backup(path, false)?;
+        }
+        // ERROR 1: file could not be created.
+        let file = File::create(path).map_err(|e| CsvFileError::into_error(path, e))?;
+        let mut out = BufWriter::new(file);
+        return write(&mut out)
+            .and_then(|_| out.flush())
+            .map_err(|source| write_error(path, source));
+    }
+
+    let temp = temp_path(path);
+    // ERROR 1: temporary file could not be created, reported for the
+    // target, which is what the caller knows.
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp)
+        .map_err(|e| CsvFileError::into_error(path, e))?;
+    let mut out = BufWriter::new(file);
+    let written = write(&mut out)
+        .and_then(|_| out.into_inner().map_err(io::IntoInnerError::into_error))
+        .and_then(|file| {
+            // The new file takes the place of the old one, so it keeps its
+            // permissions rather than getting the defaults.
+            match fs::metadata(path) {
+                Ok(old) => file.set_permissions(old.permissions())?,
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                Err(_) => {}
+            }
+            file.sync_all()
+        });
+    let renamed = written.map_err(|e| write_error(path, e)).and_then(|_| {
+        if options.backup {
+            backup(path, true)?;
+        }
+        // ERROR 20: temporary file could not replace the target.
+        fs::rename(&temp, path).map_err(|e| write_error(path, e))
+    });
+    if renamed.is_err() {
+        let _ = fs::remove_file(&temp);
+        return renamed;
+    }
+    // The rename only lasts a crash once the directory is synced too. Not
+    // every platform and file system can sync a directory, and the file is
+    // complete either way, so a failure is not reported.
+    #[cfg(unix)]
+    let _ = File::open(directory(path)).and_then(|dir| dir.sync_all());
+    Ok(())
+}
+
+/// Makes the current contents of `path`, if any, its backup. Without
+/// `keep`, by moving the file; with it, the file stays where it is.
+fn backup(path: &Path, keep: bool) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".bak");
+    let bak = PathBuf::from(name);
+    let result = match fs::remove_file(&bak) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ if !keep => fs::rename(path, &bak),
+        // A hard link shares the old contents without copying them; the
+        // rename over the target then leaves them only to the backup.
+        _ => fs::hard_link(path, &bak).or_else(|_| fs::copy(path, &bak).map(|_| ())),
+    };
+    // ERROR 1: backup could not be made.
+    result.map_err(|e| CsvFileError::into_error(&bak, e))
+}
+
+/// A new file name in the directory of `path`, so that renaming it over
+/// `path` doesn't cross file systems.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let write = WRITES.fetch_add(1, Ordering::Relaxed);
+    directory(path).join(format!(".{}.{}-{}.tmp", name, process::id(), write))
+}
+
+fn directory(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+fn write_error(path: &Path, source: io::Error) -> CsvError {
+    // ERROR 20: file could not be written.
+    CsvError::CouldNotWriteFile(CsvFileError {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    fn data() -> CsvData<i64> {
+        CsvData {
+            header: vec!["a".into(), "b".into()],
+            data: vec![vec![1, 2], vec![3, 4]],
+        }
+    }
+
+    /// Temporary files of atomic writes to `file` still lying around.
+    fn leftovers(file: &TempFile) -> usize {
+        let path = Path::new(file.path());
+        let prefix = format!(".{}.", path.file_name().unwrap().to_string_lossy());
+        fs::read_dir(directory(path))
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().starts_with(&prefix)
+            })
+            .count()
+    }
+
+    #[test]
+    fn writes_a_new_target() {
+        for atomic in [false, true] {
+            let file = TempFile::empty("new.csv");
+            let options = WriteOptions::new().atomic(atomic).backup(true);
+            write_csv_with_options(&data(), file.path(), &options).unwrap();
+            assert_eq!(file.read(), "a,b\n1,2\n3,4\n");
+            assert!(!Path::new(&file.sibling(".bak")).exists());
+            assert_eq!(leftovers(&file), 0);
+        }
+    }
+
+    #[test]
+    fn backs_up_an_existing_target() {
+        for atomic in [false, true] {
+            let file = TempFile::new("old.csv", "old\n");
+            fs::write(file.sibling(".bak"), "older\n").unwrap();
+            let options = WriteOptions::new().atomic(atomic).backup(true);
+            write_csv_with_options(&data(), file.path(), &options).unwrap();
+            assert_eq!(file.read(), "a,b\n1,2\n3,4\n");
+            assert_eq!(fs::read_to_string(file.sibling(".bak")).unwrap(), "old\n");
+            assert_eq!(leftovers(&file), 0);
+        }
+    }
+
+    #[test]
+    fn failed_atomic_write_leaves_the_target() {
+        let file = TempFile::new("kept.csv", "old\n");
+        let options = WriteOptions::new().atomic(true).backup(true);
+        let result = write_file(file.path(), &options, |out| {
+            out.write_all(b"half")?;
+            Err(io::Error::other("disk full"))
+        });
+        assert!(matches!(result, Err(CsvError::CouldNotWriteFile(_))));
+        assert_eq!(file.read(), "old\n");
+        assert!(!Path::new(&file.sibling(".bak")).exists());
+        assert_eq!(leftovers(&file), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_keeps_the_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = TempFile::new("mode.csv", "old\n");
+        fs::set_permissions(file.path(), fs::Permissions::from_mode(0o640)).unwrap();
+        let options = WriteOptions::new().atomic(true);
+        write_csv_with_options(&data(), file.path(), &options).unwrap();
+        let mode = fs::metadata(file.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::Display;
+
+use rayon::prelude::*;
+
+use super::{cast::finish, CsvData, CsvError, CsvErrorReport, CsvMapError};
+
+impl<T: Sync> CsvData<T> {
+    /// `f` applied to every record, on every core, for work such as hashing
+    /// or lookups that takes much longer than reading. The results are in
+    /// the order of the records. Fails with every error `f` returns, each
+    /// reported with the line number of its record, counting the header as
+    /// line 1.
+    pub fn par_map_rows<U, E, F>(&self, f: F) -> Result<Vec<U>, CsvErrorReport>
+    where
+        U: Send,
+        E: Display,
+        F: Fn(&[T]) -> Result<U, E> + Sync,
+    {
+        let results: Vec<_> = self
+            .data
+            .par_iter()
+            .map(|row| f(row).map_err(|e| e.to_string()))
+            .collect();
+        collect(results, self.data.len(), None)
+    }
+
+    /// `f` applied to every value of `column`, as by
+    /// [`par_map_rows`](CsvData::par_map_rows). A record too short to have
+    /// the column passes `f` nothing.
+    pub fn par_map_column<U, E, F>(&self, column: &str, f: F) -> Result<Vec<U>, CsvErrorReport>
+    where
+        U: Send,
+        E: Display,
+        F: Fn(Option<&T>) -> Result<U, E> + Sync,
+    {
+        let Some(i) = self.header.iter().position(|name| name == column) else {
+            // ERROR 16: column is not in the table.
+            return Err(CsvErrorReport {
+                errors: vec![CsvError::UnknownColumn(column.into())],
+                ..CsvErrorReport::default()
+            });
+        };
+        let results: Vec<_> = self
+            .data
+            .par_iter()
+            .map(|row| f(row.get(i)).map_err(|e| e.to_string()))
+            .collect();
+        collect(results, self.data.len(), Some((i, column)))
+    }
+}
+
+/// The values of `results`, or a report with each error, for its line and
+/// the `column` mapped, if only one was.
+fn collect<U>(
+    results: Vec<Result<U, String>>,
+    records: usize,
+    column: Option<(usize, &str)>,
+) -> Result<Vec<U>, CsvErrorReport> {
+    let mut report = CsvErrorReport::default();
+    let mut values = Vec::with_capacity(results.len());
+    for (row, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(value) => values.push(value),
+            // ERROR 21: record could not be mapped.
+            Err(reason) => report.errors.push(CsvError::MapFailed(CsvMapError {
+                line_num: row + 2,
+                column: column.map(|(i, name)| (i, name.into())),
+                reason,
+            })),
+        }
+    }
+    finish(values, report, records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> CsvData<String> {
+        CsvData {
+            header: vec!["n".into(), "s".into()],
+            data: (0..1000)
+                .map(|i| vec![i.to_string(), format!("s{}", i)])
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn results_keep_the_record_order() {
+        let data = table();
+        let doubled = data
+            .par_map_rows(|row| row[0].parse::<u64>().map(|n| n * 2))
+            .unwrap();
+        assert_eq!(doubled, (0..1000).map(|n| n * 2).collect::<Vec<u64>>());
+        let lengths = data
+            .par_map_column("s", |s| Ok::<_, String>(s.map_or(0, String::len)))
+            .unwrap();
+        assert_eq!(lengths[5], 2);
+        assert_eq!(lengths[500], 4);
+    }
+
+    #[test]
+    fn every_error_is_reported_with_its_line() {
+        let mut data = table();
+        data.data[3][0] = "x".into();
+        data.data[700][0] = "y".into();
+        let report = data.par_map_rows(|row| row[0].parse::<u64>()).unwrap_err();
+        assert_eq!(report.records, 1000);
+        let lines: Vec<_> = report.errors.iter().map(CsvError::line_num).collect();
+        assert_eq!(lines, [Some(5), Some(702)]);
+        assert!(report
+            .errors
+            .iter()
+            .all(|e| e.category() == crate::csvreader::ErrorCategory::Validation));
+        assert!(report.errors[0]
+            .to_string()
+            .starts_with("line 5: invalid digit"));
+    }
+
+    #[test]
+    fn column_errors_name_the_column() {
+        let mut data = table();
+        data.data[9].truncate(1);
+        let report = data
+            .par_map_column("s", |s| s.cloned().ok_or("missing"))
+            .unwrap_err();
+        assert_eq!(report.errors.len(), 1);
+        let error = &report.errors[0];
+        assert_eq!((error.line_num(), error.column()), (Some(11), Some(1)));
+        assert_eq!(error.code(), "map_failed");
+        assert_eq!(error.to_string(), "line 11, column s: missing");
+    }
+}
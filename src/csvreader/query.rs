@@ -0,0 +1,551 @@
+use std::{cmp::Ordering, fmt};
+
+use super::{
+    ColumnSchema, ColumnSelector, ColumnType, CsvData, CsvError, CsvFilterError, Layout, Result,
+    Row, Value,
+};
+
+/// A condition on the values of a record, such as
+/// `price > 100 && region == 'EU'`.
+///
+/// Operands are column names, numbers, quoted text, `true`, `false` and
+/// `null`. They are compared with `==`, `!=`, `<`, `<=`, `>` and `>=`, and
+/// comparisons are combined with `&&`, `||`, `!` and parentheses. Numbers
+/// compare by value, other values of different types by their text, and
+/// a `null` is only equal to another. A column on its own is true if it
+/// holds `true`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, Op, Operand),
+    Test(Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Column(String),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Filter {
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            source,
+            tokens,
+            next: 0,
+        };
+        let expr = parser.or()?;
+        if let Some(&(offset, _)) = parser.tokens.get(parser.next) {
+            return Err(parser.error(offset, "expected the end of the filter"));
+        }
+        Ok(Self {
+            source: source.into(),
+            expr,
+        })
+    }
+
+    /// The columns the filter refers to, in order of first use.
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        self.expr.columns(&mut columns);
+        columns
+    }
+
+    /// Whether `row` satisfies the filter. Missing columns are `null`.
+    pub fn matches(&self, row: &Row) -> bool {
+        self.expr.eval(row)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = CsvError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Expr {
+    fn columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+        let mut add = |operand: &'a Operand| {
+            if let Operand::Column(name) = operand {
+                if !columns.contains(&name.as_str()) {
+                    columns.push(name);
+                }
+            }
+        };
+        match self {
+            Self::Or(a, b) | Self::And(a, b) => {
+                a.columns(columns);
+                b.columns(columns);
+            }
+            Self::Not(a) => a.columns(columns),
+            Self::Compare(a, _, b) => {
+                add(a);
+                add(b);
+            }
+            Self::Test(a) => add(a),
+        }
+    }
+
+    fn eval(&self, row: &Row) -> bool {
+        match self {
+            Self::Or(a, b) => a.eval(row) || b.eval(row),
+            Self::And(a, b) => a.eval(row) && b.eval(row),
+            Self::Not(a) => !a.eval(row),
+            Self::Compare(a, op, b) => {
+                let ordering = compare(a.value(row), b.value(row));
+                match op {
+                    Op::Eq => ordering == Some(Ordering::Equal),
+                    Op::Ne => ordering != Some(Ordering::Equal),
+                    Op::Lt => ordering == Some(Ordering::Less),
+                    Op::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    Op::Gt => ordering == Some(Ordering::Greater),
+                    Op::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                }
+            }
+            Self::Test(a) => a.value(row) == &Value::Bool(true),
+        }
+    }
+}
+
+impl Operand {
+    fn value<'a>(&'a self, row: &Row<'a>) -> &'a Value {
+        match self {
+            Self::Column(name) => row.get(name).unwrap_or(&Value::Null),
+            Self::Literal(value) => value,
+        }
+    }
+}
+
+/// `None` when the two cannot be ordered: one is `null`, or a number is
+/// not a number.
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Text(a), Value::Text(b)) => Some(a.cmp(b)),
+        _ => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => Some(a.to_string().cmp(&b.to_string())),
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(Value),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+/// The tokens of `source`, each with the byte offset it starts at.
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>> {
+    // ERROR 17: filter could not be parsed.
+    let error = |offset: usize, reason: &str| {
+        CsvError::InvalidFilter(CsvFilterError {
+            filter: source.into(),
+            offset,
+            reason: reason.into(),
+        })
+    };
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        chars.next();
+        let mut then = |next: char| chars.next_if(|&(_, c)| c == next).is_some();
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' if then('&') => Token::And,
+            '|' if then('|') => Token::Or,
+            '=' if then('=') => Token::Op(Op::Eq),
+            '!' if then('=') => Token::Op(Op::Ne),
+            '!' => Token::Not,
+            '<' if then('=') => Token::Op(Op::Le),
+            '<' => Token::Op(Op::Lt),
+            '>' if then('=') => Token::Op(Op::Ge),
+            '>' => Token::Op(Op::Gt),
+            '\'' | '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, q)) if q == c => break,
+                        Some((_, q)) => text.push(q),
+                        None => return Err(error(start, "unterminated text")),
+                    }
+                }
+                Token::Literal(Value::Text(text))
+            }
+            _ if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let mut end = start + c.len_utf8();
+                let mut last = c;
+                // A sign right after an `e` is the sign of an exponent, as
+                // in `1e-5`, not a new token.
+                while let Some((i, d)) = chars.next_if(|&(_, d)| {
+                    d.is_ascii_alphanumeric()
+                        || d == '.'
+                        || (matches!(d, '+' | '-') && matches!(last, 'e' | 'E'))
+                }) {
+                    end = i + d.len_utf8();
+                    last = d;
+                }
+                let text = &source[start..end];
+                let number = [ColumnType::Int, ColumnType::Float]
+                    .into_iter()
+                    .find_map(|ty| ty.parse(text));
+                Token::Literal(number.ok_or_else(|| error(start, "expected a number"))?)
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, d)) = chars.next_if(|&(_, d)| d.is_alphanumeric() || d == '_') {
+                    end = i + d.len_utf8();
+                }
+                match &source[start..end] {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    "null" => Token::Literal(Value::Null),
+                    name => Token::Ident(name.into()),
+                }
+            }
+            _ => return Err(error(start, "unexpected character")),
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent, loosest binding first: `||`, `&&`, `!`, then
+/// comparisons.
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, offset: usize, reason: &str) -> CsvError {
+        // ERROR 17: filter could not be parsed.
+        CsvError::InvalidFilter(CsvFilterError {
+            filter: self.source.into(),
+            offset,
+            reason: reason.into(),
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    /// Where the next token starts, or the end of the source.
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.source.len(), |&(offset, _)| offset)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        self.next += usize::from(found);
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        if self.eat(&Token::Open) {
+            let expr = self.or()?;
+            if !self.eat(&Token::Close) {
+                return Err(self.error(self.offset(), "expected `)`"));
+            }
+            return Ok(expr);
+        }
+        let left = self.operand()?;
+        match self.peek() {
+            Some(&Token::Op(op)) => {
+                self.next += 1;
+                Ok(Expr::Compare(left, op, self.operand()?))
+            }
+            _ => Ok(Expr::Test(left)),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        let operand = match self.peek() {
+            Some(Token::Ident(name)) => Operand::Column(name.clone()),
+            Some(Token::Literal(value)) => Operand::Literal(value.clone()),
+            _ => return Err(self.error(self.offset(), "expected a column or a value")),
+        };
+        self.next += 1;
+        Ok(operand)
+    }
+}
+
+impl CsvData<Value> {
+    /// Keeps only the records matching `filter`.
+    pub fn filter(&mut self, filter: &Filter) -> Result<()> {
+        self.retain_matching(filter, Value::clone)
+    }
+}
+
+impl CsvData<String> {
+    /// Keeps only the records matching `filter`, with the type of each
+    /// value inferred from its text.
+    pub fn filter(&mut self, filter: &Filter) -> Result<()> {
+        self.retain_matching(filter, |text| text.parse().unwrap_or_default())
+    }
+}
+
+impl<T> CsvData<T> {
+    fn retain_matching(&mut self, filter: &Filter, value: impl Fn(&T) -> Value) -> Result<()> {
+        for column in filter.columns() {
+            if !self.header.iter().any(|name| name == column) {
+                return Err(CsvError::UnknownColumn(column.into()));
+            }
+        }
+        let columns: Vec<ColumnSchema> = self
+            .header
+            .iter()
+            .map(|name| ColumnSchema::new(name, ColumnType::Text))
+            .collect();
+        self.data.retain(|row| {
+            let values: Vec<Value> = row.iter().map(&value).collect();
+            filter.matches(&Row {
+                columns: &columns,
+                values: &values,
+            })
+        });
+        Ok(())
+    }
+}
+
+impl<T: Clone + Default> CsvData<T> {
+    /// Only the `selected` columns, in that order. Records too short to have
+    /// a column get a default value for it.
+    pub fn select(&self, selected: &[ColumnSelector]) -> Result<CsvData<T>> {
+        let keep = vec![true; self.header.len()];
+        let layout = Layout::keeping(self.header.clone(), &keep).select(selected)?;
+        let mut picked = vec![0; layout.header.len()];
+        for (i, slot) in layout.slots.iter().enumerate() {
+            if let Some(slot) = slot {
+                picked[*slot] = i;
+            }
+        }
+        let data = self
+            .data
+            .iter()
+            .map(|row| {
+                picked
+                    .iter()
+                    .map(|&i| row.get(i).cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        Ok(CsvData {
+            header: layout.header,
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether `filter` matches a record with these values.
+    fn matches(filter: &str, values: &[(&str, Value)]) -> bool {
+        let columns: Vec<ColumnSchema> = values
+            .iter()
+            .map(|(name, _)| ColumnSchema::new(name, ColumnType::Text))
+            .collect();
+        let values: Vec<Value> = values.iter().map(|(_, value)| value.clone()).collect();
+        Filter::parse(filter).unwrap().matches(&Row {
+            columns: &columns,
+            values: &values,
+        })
+    }
+
+    fn parse_error(filter: &str) -> (usize, String) {
+        match Filter::parse(filter) {
+            Err(CsvError::InvalidFilter(e)) => (e.offset, e.reason),
+            other => panic!("{:?} parsed as {:?}", filter, other),
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let a = |a: bool, b: bool, c: bool| {
+            let values = [
+                ("a", Value::Bool(a)),
+                ("b", Value::Bool(b)),
+                ("c", Value::Bool(c)),
+            ];
+            (
+                matches("a || b && c", &values),
+                matches("(a || b) && c", &values),
+            )
+        };
+        assert_eq!(a(true, false, false), (true, false));
+        assert_eq!(a(false, true, true), (true, true));
+        assert_eq!(a(false, true, false), (false, false));
+    }
+
+    #[test]
+    fn not_binds_tightest() {
+        let values = [("a", Value::Bool(false)), ("b", Value::Bool(false))];
+        assert!(!matches("!a && b", &values));
+        assert!(matches("!(a && b)", &values));
+        assert!(matches("!!!a", &values));
+    }
+
+    #[test]
+    fn comparisons() {
+        let values = [
+            ("price", Value::Int(150)),
+            ("region", Value::Text("EU".into())),
+            ("note", Value::Null),
+        ];
+        assert!(matches("price > 100 && region == 'EU'", &values));
+        assert!(matches("price >= 150 && price <= 150", &values));
+        assert!(!matches("price < 150 || region != \"EU\"", &values));
+        assert!(matches("note == null && !(note != null)", &values));
+        assert!(!matches("note < 1 || note >= 1", &values));
+        assert!(matches("missing == null", &values));
+    }
+
+    #[test]
+    fn number_literals() {
+        let values = [("x", Value::Float(0.00001)), ("n", Value::Int(-3))];
+        assert!(matches("x == 1e-5", &values));
+        assert!(matches("x == 1E-5 && x < 1e+0", &values));
+        assert!(matches("x == 0.00001 && x > .000001", &values));
+        assert!(matches("n == -3 && n < 2.5 && n > -3.5", &values));
+        assert!(matches("n==-3", &values));
+    }
+
+    #[test]
+    fn columns_in_order_of_first_use() {
+        let filter = Filter::parse("b > 1 || (a == b && !c)").unwrap();
+        assert_eq!(filter.columns(), ["b", "a", "c"]);
+        assert_eq!(filter.to_string(), "b > 1 || (a == b && !c)");
+    }
+
+    #[test]
+    fn bad_filters_are_errors() {
+        assert_eq!(
+            parse_error("a >"),
+            (3, "expected a column or a value".into())
+        );
+        assert_eq!(parse_error("(a == 1"), (7, "expected `)`".into()));
+        assert_eq!(
+            parse_error("a == 1)"),
+            (6, "expected the end of the filter".into())
+        );
+        assert_eq!(parse_error("a == 'EU"), (5, "unterminated text".into()));
+        assert_eq!(parse_error("a == 1x"), (5, "expected a number".into()));
+        assert_eq!(parse_error("a == 1e-"), (5, "expected a number".into()));
+        assert_eq!(parse_error("a & b"), (2, "unexpected character".into()));
+        assert_eq!(parse_error("a = 1"), (2, "unexpected character".into()));
+        assert_eq!(parse_error(""), (0, "expected a column or a value".into()));
+        assert_eq!(
+            parse_error("a == b == c"),
+            (7, "expected the end of the filter".into())
+        );
+    }
+
+    #[test]
+    fn unknown_columns_fail_the_filter() {
+        let mut data = CsvData {
+            header: vec!["a".to_string()],
+            data: vec![vec!["1".to_string()], vec!["2".to_string()]],
+        };
+        let filter = Filter::parse("a > 1").unwrap();
+        data.filter(&filter).unwrap();
+        assert_eq!(data.data, [["2"]]);
+        let filter = Filter::parse("b > 1").unwrap();
+        assert!(matches!(
+            data.filter(&filter),
+            Err(CsvError::UnknownColumn(column)) if column == "b"
+        ));
+    }
+
+    fn table() -> CsvData<String> {
+        let rows: [&[&str]; 3] = [&["1", "a", "x"], &["2", "b", "y"], &["3"]];
+        CsvData {
+            header: vec!["n".into(), "s".into(), "t".into()],
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn selects_and_reorders_columns() {
+        let name = |name: &str| ColumnSelector::Name(name.into());
+        let selected = table()
+            .select(&[name("t"), ColumnSelector::Index(0)])
+            .unwrap();
+        assert_eq!(selected.header, ["t", "n"]);
+        assert_eq!(selected.data, [["x", "1"], ["y", "2"], ["", "3"]]);
+
+        let selected = table().select(&[name("s")]).unwrap();
+        assert_eq!(selected.header, ["s"]);
+        assert_eq!(selected.data, [["a"], ["b"], [""]]);
+    }
+
+    #[test]
+    fn selecting_an_unknown_column_fails() {
+        let result = table().select(&[ColumnSelector::Name("m".into())]);
+        assert!(matches!(result, Err(CsvError::UnknownColumn(c)) if c == "m"));
+    }
+}
@@ -0,0 +1,828 @@
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{iter, mem, str::FromStr};
+
+use super::{
+    header, tokenizer, ColumnSelector, CsvData, CsvError, CsvErrorReport, CsvReadOptions,
+    CsvRecordLen, CsvValuePos, ErrorAction, FlatCsvData, OnError, RecordContext, Result, Severity,
+    SkippedRecord, Strictness,
+};
+
+pub(super) const BOM: &[u8] = b"\xEF\xBB\xBF";
+/// Number of records [`split_lines`] averages to estimate how many there are.
+const SAMPLE_LINES: usize = 100;
+/// Number of records per `parse_chunk` tracing span.
+#[cfg(feature = "tracing")]
+const TRACE_CHUNK: usize = 100_000;
+
+/// Like [`read_csv`](super::read_csv), but for CSV text that is already in
+/// memory, such as an uploaded file in the browser. Touches neither the file
+/// system nor threads.
+pub fn read_csv_from_bytes<T: Default + FromStr>(input: &[u8]) -> Result<CsvData<T>> {
+    parse_csv(input, &CsvReadOptions::default()).map(|(data, _)| data)
+}
+
+/// Parses CSV text that is already in memory, handling problems according
+/// to `options` like [`read_csv_with_options`](super::read_csv_with_options).
+/// Needs neither a file system nor `std`.
+pub fn parse_csv<T: Default + FromStr>(
+    input: &[u8],
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let sink = Sink::new(options, false);
+    parse_lines(&split_lines(input, options), sink, &mut FromStrParser)
+}
+
+/// Like [`parse_csv`], but keeps going past bad lines, like
+/// [`read_csv_validated`](super::read_csv_validated).
+pub fn parse_csv_validated<T: Default + FromStr>(
+    input: &[u8],
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let sink = Sink::new(options, true);
+    parse_lines(&split_lines(input, options), sink, &mut FromStrParser)
+}
+
+/// Like [`parse_csv`], but stores the records in one buffer, see
+/// [`FlatCsvData`].
+pub fn parse_csv_flat<T: Default + FromStr>(
+    input: &[u8],
+    options: &CsvReadOptions,
+) -> Result<(FlatCsvData<T>, CsvErrorReport)> {
+    let sink = Sink::new(options, false);
+    parse_lines_flat(&split_lines(input, options), sink, &mut FromStrParser)
+}
+
+/// The records of `input`, without their `\n` or `\r\n`. A final line
+/// break does not start another record, one in a quoted field does not end
+/// one.
+pub(super) fn split_lines<'a>(input: &'a [u8], options: &CsvReadOptions) -> Vec<&'a [u8]> {
+    // Reserve for as many records as the first few suggest, so that neither
+    // this nor the parsed data has to grow as a large file is read.
+    let (sampled, sampled_len) = tokenizer::records(input, options)
+        .take(SAMPLE_LINES)
+        .fold((0, 0), |(n, len), line| (n + 1, len + line.len() + 1));
+    let average = (sampled_len / sampled.max(1)).max(1);
+    let mut lines = Vec::with_capacity(input.len() / average + 1);
+    lines.extend(tokenizer::records(input, options));
+    lines
+}
+
+/// Decides what happens to each problem found while parsing.
+pub(super) struct Sink<'a> {
+    options: &'a CsvReadOptions,
+    collect_errors: bool,
+    pub(super) report: CsvErrorReport,
+    /// First problem of the current record, when it is going to be skipped.
+    pending_skip: Option<CsvError>,
+}
+
+impl<'a> Sink<'a> {
+    pub(super) fn new(options: &'a CsvReadOptions, collect_errors: bool) -> Self {
+        Self {
+            options,
+            collect_errors,
+            report: CsvErrorReport::default(),
+            pending_skip: None,
+        }
+    }
+
+    /// Aborts with `e`, or records it if errors are being collected or
+    /// skipped.
+    pub(super) fn error(&mut self, e: CsvError) -> Result<()> {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(code = e.code(), line = e.line_num(), "{}", e);
+        if self.collect_errors {
+            if !self.at_limit() {
+                self.report.errors.push(e);
+            }
+            return Ok(());
+        }
+        match self.options.on_error {
+            OnError::Abort => Err(e),
+            OnError::Skip => {
+                self.pending_skip.get_or_insert(e);
+                Ok(())
+            }
+        }
+    }
+
+    /// Records the current record as skipped if it had a problem.
+    pub(super) fn finish_record(&mut self, line_num: usize, raw: &str) {
+        self.report.records += 1;
+        if let Some(reason) = self.pending_skip.take() {
+            if self.at_limit() {
+                return;
+            }
+            self.report.skipped.push(SkippedRecord {
+                line_num,
+                reason,
+                raw: raw.into(),
+            });
+        }
+    }
+
+    /// Returns whether the caller may recover from `e`.
+    pub(super) fn warning(&mut self, e: CsvError) -> Result<bool> {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(code = e.code(), line = e.line_num(), "{}", e);
+        match self.options.strictness {
+            Strictness::Strict => self.error(e).map(|_| false),
+            Strictness::Warn => {
+                if !self.at_limit() {
+                    self.report.warnings.push(e);
+                }
+                Ok(true)
+            }
+            Strictness::Lenient => Ok(true),
+        }
+    }
+
+    /// Whether `max_errors` problems have been collected already. Marks the
+    /// report as truncated, which stops parsing after the current record.
+    pub(super) fn at_limit(&mut self) -> bool {
+        let limit = self.options.max_errors.unwrap_or(usize::MAX);
+        self.report.truncated |= self.report.len() >= limit;
+        self.report.truncated
+    }
+
+    /// Asks the error hook, if any, what to do about `e`.
+    #[cfg(feature = "std")]
+    pub(super) fn consult(&self, e: &CsvError, ctx: &RecordContext) -> ErrorAction {
+        match &self.options.error_hook {
+            Some(hook) => hook.call(e, ctx),
+            None => ErrorAction::UsePolicy,
+        }
+    }
+
+    /// There are no error hooks without `std`.
+    #[cfg(not(feature = "std"))]
+    pub(super) fn consult(&self, _: &CsvError, _: &RecordContext) -> ErrorAction {
+        ErrorAction::UsePolicy
+    }
+
+    /// Carries out `action` for `e`. Returns whether the caller may recover.
+    pub(super) fn apply(&mut self, action: ErrorAction, e: CsvError) -> Result<bool> {
+        match action {
+            ErrorAction::UsePolicy | ErrorAction::Fix(_) => match e.severity() {
+                Severity::Warning => self.warning(e),
+                Severity::Error => self.error(e).map(|_| false),
+            },
+            ErrorAction::Skip => {
+                self.pending_skip.get_or_insert(e);
+                Ok(false)
+            }
+            ErrorAction::Abort => Err(e),
+        }
+    }
+
+    pub(super) fn report(&mut self, e: CsvError, ctx: &RecordContext) -> Result<bool> {
+        let action = self.consult(&e, ctx);
+        self.apply(action, e)
+    }
+}
+
+/// Where the fields of a record end up in the result.
+pub(super) struct Layout {
+    /// Names of the result's columns.
+    pub(super) header: Vec<String>,
+    /// For each field of a record, the result column it goes to, if any.
+    pub(super) slots: Vec<Option<usize>>,
+}
+
+/// How the fields of a record are turned into values of `T`.
+pub(super) trait FieldParser<T> {
+    /// Works out the result's columns from the file's header.
+    fn layout(&mut self, header: Vec<String>, options: &CsvReadOptions) -> Result<Layout>;
+
+    /// Parses field number `column` of line `line_num`.
+    fn parse(&self, text: &str, line_num: usize, column: usize) -> Result<T>;
+
+    /// Checks a row whose fields all parsed, before it is added to the
+    /// result. Any problem returned keeps the row out.
+    fn finish_row(&mut self, _row: &mut Vec<T>, _line_num: usize) -> Vec<CsvError> {
+        Vec::new()
+    }
+}
+
+/// Parses every field with `T::from_str`.
+pub(super) struct FromStrParser;
+
+impl<T: FromStr> FieldParser<T> for FromStrParser {
+    fn layout(&mut self, header: Vec<String>, options: &CsvReadOptions) -> Result<Layout> {
+        // ERROR 9: header has duplicate column names.
+        let (header, keep) = header::dedup_header(header, options.duplicate_headers)?;
+        // ERROR 8: header is not the expected one.
+        if let Some(expected) = &options.expected_header {
+            header::check_header(&header, expected, false)?;
+        }
+        let layout = Layout::keeping(header, &keep);
+        match &options.columns {
+            Some(columns) => layout.select(columns),
+            None => Ok(layout),
+        }
+    }
+
+    fn parse(&self, text: &str, line_num: usize, column: usize) -> Result<T> {
+        text.parse::<T>().map_err(|_| {
+            let pos = CsvValuePos {
+                line_num,
+                column,
+                value: text.into(),
+            };
+            // WARNING 4: empty cell that can be defaulted.
+            if text.is_empty() {
+                CsvError::EmptyValue(pos)
+            } else {
+                // ERROR 5: could not parse from string.
+                CsvError::CouldNotParseValue(pos)
+            }
+        })
+    }
+}
+
+impl Layout {
+    /// The fields for which `keep` is set, in their original order.
+    pub(super) fn keeping(header: Vec<String>, keep: &[bool]) -> Self {
+        let mut next = 0;
+        let slots = keep
+            .iter()
+            .map(|&keep| {
+                keep.then(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect();
+        Self { header, slots }
+    }
+
+    /// Keeps only the `selected` result columns, in that order.
+    pub(super) fn select(self, selected: &[ColumnSelector]) -> Result<Self> {
+        let mut picked: Vec<usize> = Vec::new();
+        for selector in selected {
+            // ERROR 16: selected column is not in the file.
+            let unknown = || CsvError::UnknownColumn(selector.to_string());
+            let columns = match selector {
+                ColumnSelector::Name(name) => {
+                    let i = self.header.iter().position(|n| n == name);
+                    vec![i.ok_or_else(unknown)?]
+                }
+                ColumnSelector::Index(i) if *i < self.header.len() => vec![*i],
+                ColumnSelector::Range(from, to) => {
+                    let to = to.unwrap_or(self.header.len().saturating_sub(1));
+                    if to >= self.header.len() || from > &to {
+                        return Err(unknown());
+                    }
+                    (*from..=to).collect()
+                }
+                ColumnSelector::Index(_) => return Err(unknown()),
+            };
+            for i in columns {
+                if !picked.contains(&i) {
+                    picked.push(i);
+                }
+            }
+        }
+        let header = picked.iter().map(|&i| self.header[i].clone()).collect();
+        let slots = self
+            .slots
+            .iter()
+            .map(|slot| picked.iter().position(|&i| Some(i) == *slot))
+            .collect();
+        Ok(Self { header, slots })
+    }
+}
+
+/// Parses the header and records. A record with problems that could not be
+/// recovered from is left out of the result.
+pub(super) fn parse_lines<T: Default>(
+    lines: &[impl AsRef<[u8]>],
+    sink: Sink,
+    parser: &mut dyn FieldParser<T>,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let mut data: Vec<Vec<T>> = Vec::with_capacity(lines.len());
+    let engine = each_record(lines, sink, parser, |row| data.push(mem::take(row)))?;
+    Ok((
+        CsvData {
+            header: engine.header,
+            data,
+        },
+        engine.sink.report,
+    ))
+}
+
+/// Like [`parse_lines`], but stores the records back to back.
+pub(super) fn parse_lines_flat<T: Default>(
+    lines: &[impl AsRef<[u8]>],
+    sink: Sink,
+    parser: &mut dyn FieldParser<T>,
+) -> Result<(FlatCsvData<T>, CsvErrorReport)> {
+    let mut values: Vec<T> = Vec::new();
+    let engine = each_record(lines, sink, parser, |row| {
+        if values.is_empty() {
+            values.reserve(row.len() * lines.len());
+        }
+        values.append(row);
+    })?;
+    Ok((
+        FlatCsvData {
+            header: engine.header,
+            values,
+        },
+        engine.sink.report,
+    ))
+}
+
+/// Parses `lines` one record at a time into the same buffer, handing each
+/// usable one to `keep`, which may take its values.
+fn each_record<'a, T: Default>(
+    lines: &[impl AsRef<[u8]>],
+    sink: Sink<'a>,
+    parser: &mut dyn FieldParser<T>,
+    mut keep: impl FnMut(&mut Vec<T>),
+) -> Result<Engine<'a>> {
+    // ERROR 4: file was empty
+    if lines.is_empty() {
+        return Err(CsvError::FileIsEmpty);
+    }
+    let options = sink.options;
+    let more = &lines[1..options.header_lines().clamp(1, lines.len())];
+    let (mut engine, first) = Engine::start(lines[0].as_ref(), more, sink, parser)?;
+    let skip = match first {
+        Some(_) => 0,
+        None => 1 + more.len(),
+    };
+    let end = lines.len() - footer_len(lines[skip..].iter().map(AsRef::as_ref), options);
+    let mut row = Vec::new();
+    // Line numbers are 1-based, counting the header, if any, from line 1. A
+    // record with quoted line breaks spans several lines.
+    let mut line_num = 1 + lines[..skip]
+        .iter()
+        .map(|line| 1 + line_breaks(line.as_ref()))
+        .sum::<usize>();
+
+    #[cfg(feature = "tracing")]
+    let mut _chunk = None;
+
+    for (i, line) in lines[..end].iter().enumerate().skip(skip) {
+        #[cfg(feature = "tracing")]
+        if (i - skip) % TRACE_CHUNK == 0 {
+            // Close the previous chunk first, so chunks don't nest.
+            _chunk = None;
+            _chunk = Some(tracing::debug_span!("parse_chunk", first_line = line_num).entered());
+        }
+        // The BOM was already taken off the first line.
+        let raw = match first {
+            Some(first) if i == 0 => first,
+            _ => line.as_ref(),
+        };
+        let record_line = line_num;
+        line_num += 1 + line_breaks(raw);
+        if engine.record_into(record_line, raw, parser, &mut row)? {
+            keep(&mut row);
+        }
+        if engine.done() {
+            break;
+        }
+    }
+    Ok(engine)
+}
+
+/// Number of `records`, the last of a file, that are its footer: the last
+/// `skip_footer`, and those before them that pass the `footer` test.
+pub(super) fn footer_len<'l>(
+    records: impl DoubleEndedIterator<Item = &'l [u8]> + ExactSizeIterator,
+    options: &CsvReadOptions,
+) -> usize {
+    let skipped = options.skip_footer.min(records.len());
+    let tested = records
+        .rev()
+        .skip(skipped)
+        .take_while(|raw| options.is_footer(raw))
+        .count();
+    skipped + tested
+}
+
+/// Number of line breaks inside a record, in its quoted fields.
+pub(super) fn line_breaks(raw: &[u8]) -> usize {
+    raw.iter().filter(|&&b| b == b'\n').count()
+}
+
+/// Turns raw lines into records, once the header is known.
+pub(super) struct Engine<'a> {
+    pub(super) sink: Sink<'a>,
+    /// Names of the result's columns.
+    pub(super) header: Vec<String>,
+    /// Number of fields in a record, including dropped columns.
+    width: usize,
+    slots: Vec<Option<usize>>,
+    /// Whether the kept columns are in file order, slot `i` being the
+    /// `i`-th kept field.
+    in_order: bool,
+}
+
+impl<'a> Engine<'a> {
+    /// Reads the header from the first line of the file, and from `more`
+    /// for a header of several lines. Without a header, the first line is
+    /// returned as the first record, minus any BOM.
+    pub(super) fn start<'l, T>(
+        mut first: &'l [u8],
+        more: &[impl AsRef<[u8]>],
+        mut sink: Sink<'a>,
+        parser: &mut dyn FieldParser<T>,
+    ) -> Result<(Self, Option<&'l [u8]>)> {
+        // WARNING 1: file starts with a byte order mark.
+        if first.starts_with(BOM) {
+            first = &first[BOM.len()..];
+            let ctx = RecordContext {
+                line_num: 1,
+                raw: &String::from_utf8_lossy(first),
+                header: &[],
+            };
+            sink.report(CsvError::ByteOrderMark, &ctx)?;
+        }
+        let (header, first) = if sink.options.has_header {
+            let mut rows: Vec<Vec<String>> = Vec::with_capacity(1 + more.len());
+            let mut line_num = 1;
+            for raw in iter::once(first).chain(more.iter().map(AsRef::as_ref)) {
+                let (line, _) = decode_line(raw, line_num, &[], &mut sink)?;
+                // There is no skipping a broken header.
+                if let Some(e) = sink.pending_skip.take() {
+                    return Err(e);
+                }
+                let names = tokenizer::fields(&line, sink.options);
+                rows.push(names.map(|(_, name)| name.into_owned()).collect());
+                line_num += 1 + line_breaks(raw);
+            }
+            let header = match rows.len() {
+                1 => rows.swap_remove(0),
+                _ => header::join_header_rows(rows, &sink.options.header_separator),
+            };
+            (header, None)
+        } else {
+            // The first record is decoded, and any problem reported, later.
+            let width = tokenizer::fields(&String::from_utf8_lossy(first), sink.options).count();
+            let header: Vec<String> = (1..=width).map(|i| format!("column_{}", i)).collect();
+            (header, Some(first))
+        };
+        let header: Vec<String> = header
+            .into_iter()
+            .map(|name| sink.options.renamed(name))
+            .collect();
+        let width = header.len();
+        let Layout { header, slots } = parser.layout(header, sink.options)?;
+        let in_order = slots.iter().flatten().copied().eq(0..header.len());
+        let engine = Self {
+            sink,
+            header,
+            width,
+            slots,
+            in_order,
+        };
+        Ok((engine, first))
+    }
+
+    /// Whether reading should stop, because `max_errors` was reached.
+    pub(super) fn done(&self) -> bool {
+        self.sink.report.truncated
+    }
+
+    /// Parses one record. Returns `None` if it had problems that could not
+    /// be recovered from.
+    #[cfg(feature = "std")]
+    pub(super) fn record<T: Default>(
+        &mut self,
+        line_num: usize,
+        raw: &[u8],
+        parser: &mut dyn FieldParser<T>,
+    ) -> Result<Option<Vec<T>>> {
+        let mut values = Vec::new();
+        let valid = self.record_into(line_num, raw, parser, &mut values)?;
+        Ok(valid.then_some(values))
+    }
+
+    /// Like [`Engine::record`], but parses into `values`, replacing what it
+    /// held, and returns whether the record is usable.
+    pub(super) fn record_into<T: Default>(
+        &mut self,
+        line_num: usize,
+        raw: &[u8],
+        parser: &mut dyn FieldParser<T>,
+        values: &mut Vec<T>,
+    ) -> Result<bool> {
+        let Self {
+            sink,
+            header,
+            width,
+            slots,
+            in_order,
+        } = self;
+        let width = *width;
+        let delimiter = sink.options.delimiter;
+        let (decoded, mut valid) = decode_line(raw, line_num, header, sink)?;
+        let mut line = decoded.as_ref();
+        let ctx = RecordContext {
+            line_num,
+            raw: &decoded,
+            header,
+        };
+
+        // WARNING 3: line ends with a delimiter, giving it an empty extra field.
+        if line.ends_with(delimiter) && tokenizer::fields(line, sink.options).count() == width + 1 {
+            valid &= sink.report(CsvError::TrailingDelimiter(line_num), &ctx)?;
+            line = &line[..line.len() - delimiter.len_utf8()];
+        }
+
+        // Each field is parsed as it is split off. With the columns in file
+        // order, values are pushed straight onto the row; otherwise they are
+        // put in their slot. Columns without a field, such as the end of a
+        // short line, keep their default value.
+        values.clear();
+        values.reserve(header.len());
+        if !*in_order {
+            values.resize_with(header.len(), T::default);
+        }
+        let mut num_entries = 0;
+        for (column, (_, field)) in tokenizer::fields(line, sink.options).enumerate() {
+            num_entries += 1;
+            // Fields beyond the header width have no slot and are not parsed.
+            let Some(slot) = slots.get(column).copied().flatten() else {
+                continue;
+            };
+            let field = if sink.options.is_null(&field) {
+                ""
+            } else {
+                &field
+            };
+            let value = match parser.parse(field, line_num, column) {
+                Ok(value) => Some(value),
+                Err(e) => match sink.consult(&e, &ctx) {
+                    ErrorAction::Fix(text) => match parser.parse(&text, line_num, column) {
+                        Ok(value) => Some(value),
+                        Err(e) => {
+                            valid = false;
+                            sink.error(e)?;
+                            None
+                        }
+                    },
+                    action => {
+                        valid &= sink.apply(action, e)?;
+                        None
+                    }
+                },
+            };
+            if *in_order {
+                values.push(value.unwrap_or_default());
+            } else if let Some(value) = value {
+                values[slot] = value;
+            }
+        }
+        values.resize_with(header.len(), T::default);
+
+        let short = num_entries < width && !sink.options.pad_short_lines;
+        let long = num_entries > width && !sink.options.truncate_long_lines;
+        // ERROR 6: line was too short.
+        // ERROR 7 (hidden): line was too long.
+        if short || long {
+            valid = false;
+            let boundary = long.then(|| {
+                tokenizer::fields(line, sink.options)
+                    .nth(width)
+                    .map_or(line.len(), |(start, _)| start - delimiter.len_utf8())
+            });
+            let e = CsvError::RecordLengthMismatch(CsvRecordLen {
+                line_num,
+                expected: width,
+                num_entries,
+                raw: line.into(),
+                boundary,
+            });
+            sink.report(e, &ctx)?;
+        }
+
+        if valid {
+            for e in parser.finish_row(values, line_num) {
+                valid &= sink.report(e, &ctx)?;
+            }
+        }
+
+        sink.finish_record(line_num, &decoded);
+        Ok(valid)
+    }
+}
+
+/// Decodes a raw line, replacing invalid UTF-8 if the sink allows it. Also
+/// returns whether the line is still usable.
+pub(super) fn decode_line<'a>(
+    raw: &'a [u8],
+    line_num: usize,
+    header: &[String],
+    sink: &mut Sink,
+) -> Result<(Cow<'a, str>, bool)> {
+    match core::str::from_utf8(raw) {
+        Ok(line) => Ok((line.into(), true)),
+        // WARNING 2: line is not valid UTF-8.
+        Err(_) => {
+            let line = String::from_utf8_lossy(raw);
+            let ctx = RecordContext {
+                line_num,
+                raw: &line,
+                header,
+            };
+            let usable = sink.report(CsvError::InvalidUtf8(line_num), &ctx)?;
+            Ok((line, usable))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::csvreader::{
+        read_csv_validated_with_options, read_csv_with_options, testutil::TempFile,
+    };
+
+    const MIXED: &str = "a,b\n1,2\nx,3\n4\n5,6,7\n8,9\n";
+
+    fn lines(errors: &[CsvError]) -> Vec<Option<usize>> {
+        errors.iter().map(CsvError::line_num).collect()
+    }
+
+    #[test]
+    fn collect_all_reports_every_problem_and_keeps_the_good_records() {
+        let file = TempFile::new("sink_collect.csv", MIXED);
+        let (data, report) =
+            read_csv_validated_with_options::<i64>(file.path(), &CsvReadOptions::default())
+                .unwrap();
+        assert_eq!(data.data, [[1, 2], [8, 9]]);
+        assert_eq!(report.records, 5);
+        assert_eq!(lines(&report.errors), [Some(3), Some(4), Some(5)]);
+        assert!(matches!(report.errors[0], CsvError::CouldNotParseValue(_)));
+        assert!(matches!(
+            report.errors[1],
+            CsvError::RecordLengthMismatch(_)
+        ));
+        assert!(report.skipped.is_empty() && !report.truncated);
+    }
+
+    #[test]
+    fn skipped_records_are_listed_with_their_first_problem() {
+        let file = TempFile::new("sink_skip.csv", "a,b\n1,2\nx,y\n4\n");
+        let options = CsvReadOptions::default().on_error(OnError::Skip);
+        let (data, report) = read_csv_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(data.data, [[1, 2]]);
+        assert_eq!(report.records, 3);
+        assert!(report.errors.is_empty());
+        let skipped: Vec<_> = report
+            .skipped
+            .iter()
+            .map(|s| (s.line_num, s.raw.as_str(), s.reason.column()))
+            .collect();
+        assert_eq!(skipped, [(3, "x,y", Some(0)), (4, "4", None)]);
+
+        // Without skipping, the first problem fails the read.
+        match read_csv_with_options::<i64>(file.path(), &CsvReadOptions::default()) {
+            Err(e) => assert_eq!((e.line_num(), e.column()), (Some(3), Some(0))),
+            Ok(data) => panic!("expected an error, got {:?}", data),
+        }
+    }
+
+    #[test]
+    fn short_and_long_records_are_errors_however_strict() {
+        let file = TempFile::new("sink_lengths.csv", "a,b\n1\n2,3,4\n");
+        for strictness in [Strictness::Strict, Strictness::Warn, Strictness::Lenient] {
+            let options = CsvReadOptions::default().strictness(strictness);
+            let (data, report) =
+                read_csv_validated_with_options::<i64>(file.path(), &options).unwrap();
+            assert!(data.data.is_empty());
+            let lengths: Vec<_> = report
+                .errors
+                .iter()
+                .map(|e| match e {
+                    CsvError::RecordLengthMismatch(len) => (len.line_num, len.num_entries),
+                    e => panic!("expected a length mismatch, got {:?}", e),
+                })
+                .collect();
+            assert_eq!(lengths, [(2, 1), (3, 3)], "{:?}", strictness);
+        }
+    }
+
+    #[test]
+    fn warnings_are_errors_only_when_strict() {
+        let file = TempFile::new("sink_warnings.csv", "a,b\n1,2,\n");
+        let read = |strictness| {
+            let options = CsvReadOptions::default().strictness(strictness);
+            read_csv_validated_with_options::<i64>(file.path(), &options).unwrap()
+        };
+        let (data, report) = read(Strictness::Strict);
+        assert!(data.data.is_empty());
+        assert!(matches!(
+            report.errors[..],
+            [CsvError::TrailingDelimiter(2)]
+        ));
+
+        let (data, report) = read(Strictness::Warn);
+        assert_eq!(data.data, [[1, 2]]);
+        assert!(report.errors.is_empty());
+        assert!(matches!(
+            report.warnings[..],
+            [CsvError::TrailingDelimiter(2)]
+        ));
+
+        let (data, report) = read(Strictness::Lenient);
+        assert_eq!(data.data, [[1, 2]]);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn records_can_be_padded_and_truncated_to_the_header() {
+        let file = TempFile::new("sink_pad.csv", "a,b,c\n1\n2,3,4,5\n6,7,8\n");
+        let options = CsvReadOptions::default()
+            .pad_short_lines(true)
+            .truncate_long_lines(true);
+        let (data, report) = read_csv_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(data.data, [[1, 0, 0], [2, 3, 4], [6, 7, 8]]);
+        assert!(report.is_empty());
+
+        // Either alone leaves the other kind an error.
+        let options = CsvReadOptions::default().pad_short_lines(true);
+        let (data, report) = read_csv_validated_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(data.data, [[1, 0, 0], [6, 7, 8]]);
+        assert_eq!(lines(&report.errors), [Some(3)]);
+        let options = CsvReadOptions::default().truncate_long_lines(true);
+        let (data, report) = read_csv_validated_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(data.data, [[2, 3, 4], [6, 7, 8]]);
+        assert_eq!(lines(&report.errors), [Some(2)]);
+    }
+
+    #[test]
+    fn reading_stops_at_max_errors() {
+        let file = TempFile::new("sink_max.csv", "a\nx\n1\nx\nx\n2\nx\n");
+        let options = CsvReadOptions::default().max_errors(2);
+        let (data, report) = read_csv_validated_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(lines(&report.errors), [Some(2), Some(4)]);
+        assert!(report.truncated);
+        // The record that found the limit full is the last one read.
+        assert_eq!(data.data, [[1]]);
+        assert_eq!(report.records, 4);
+
+        let options = CsvReadOptions::default()
+            .on_error(OnError::Skip)
+            .max_errors(2);
+        let (data, report) = read_csv_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(data.data, [[1]]);
+        let skipped: Vec<usize> = report.skipped.iter().map(|s| s.line_num).collect();
+        assert_eq!(skipped, [2, 4]);
+        assert!(report.truncated);
+
+        let options = CsvReadOptions::default().max_errors(4);
+        let (_, report) = read_csv_validated_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(report.errors.len(), 4);
+        assert!(!report.truncated);
+    }
+
+    #[test]
+    fn the_hook_is_consulted_for_every_problem() {
+        let file = TempFile::new("sink_hook.csv", "a,b\n1,x\ny,2\n3\n4,5\n");
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let hook_seen = Arc::clone(&seen);
+        let options = CsvReadOptions::default().on_problem(move |e, ctx| {
+            hook_seen
+                .lock()
+                .unwrap()
+                .push((ctx.line_num, ctx.raw.to_string(), e.code()));
+            match ctx.line_num {
+                2 => ErrorAction::Fix("0".into()),
+                3 => ErrorAction::Skip,
+                _ => ErrorAction::UsePolicy,
+            }
+        });
+        let (data, report) = read_csv_validated_with_options::<i64>(file.path(), &options).unwrap();
+        assert_eq!(data.data, [[1, 0], [4, 5]]);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].line_num, 3);
+        assert_eq!(lines(&report.errors), [Some(4)]);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                (2, "1,x".to_string(), "could_not_parse_value"),
+                (3, "y,2".to_string(), "could_not_parse_value"),
+                (4, "3".to_string(), "record_length_mismatch"),
+            ]
+        );
+
+        let options = CsvReadOptions::default().on_problem(|_, _| ErrorAction::Abort);
+        match read_csv_validated_with_options::<i64>(file.path(), &options) {
+            Err(e) => assert_eq!(e.line_num(), Some(2)),
+            Ok(data) => panic!("expected the hook to abort, got {:?}", data),
+        }
+    }
+}
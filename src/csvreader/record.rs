@@ -0,0 +1,147 @@
+use super::{
+    header, read, CsvError, CsvErrorReport, CsvHeaderDiff, CsvReadOptions, CsvValuePos,
+    FieldParser, Layout, Result,
+};
+
+/// A column a [`CsvRecord`] is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordColumn {
+    pub name: &'static str,
+    /// Whether the file has to have this column.
+    pub required: bool,
+}
+
+/// A struct that can be read from the records of a file, by column name.
+///
+/// Usually derived with `#[derive(CsvRecord)]` (`derive` feature):
+///
+/// ```ignore
+/// #[derive(CsvRecord)]
+/// struct Trade {
+///     id: u64,
+///     #[csv(rename = "px")]
+///     price: f64,
+///     #[csv(default)]
+///     note: String,
+///     #[csv(parse_with = "parse_side")]
+///     side: Side,
+/// }
+/// ```
+///
+/// Fields are parsed with `FromStr` unless `parse_with` names a
+/// `fn(&str) -> Result<T, E>`. Fields marked `default` may be missing from
+/// the file or empty, and then get `Default::default()`.
+pub trait CsvRecord: Sized {
+    fn columns() -> &'static [RecordColumn];
+
+    /// Builds a record from `fields`, indexed like [`CsvRecord::columns`].
+    /// `None` means the file has no such column. On failure, returns the index
+    /// of the field that could not be parsed.
+    fn from_fields(fields: &[Option<&str>]) -> std::result::Result<Self, usize>;
+}
+
+/// Reads `filename` into a list of `R`, see [`CsvRecord`].
+pub fn read_csv_records<R: CsvRecord>(filename: &str) -> Result<Vec<R>> {
+    read_csv_records_with_options(filename, &CsvReadOptions::default()).map(|(records, _)| records)
+}
+
+/// [`read_csv_records`] with explicit options, see
+/// [`read_csv_with_options`](super::read_csv_with_options).
+pub fn read_csv_records_with_options<R: CsvRecord>(
+    filename: &str,
+    options: &CsvReadOptions,
+) -> Result<(Vec<R>, CsvErrorReport)> {
+    let mut parser = RecordParser::new();
+    let (_, report) = read(filename, options, false, &mut parser)?;
+    Ok((parser.records, report))
+}
+
+/// [`read_csv_records`] in collect-all mode, see
+/// [`read_csv_validated`](super::read_csv_validated).
+pub fn read_csv_records_validated<R: CsvRecord>(
+    filename: &str,
+    options: &CsvReadOptions,
+) -> Result<(Vec<R>, CsvErrorReport)> {
+    let mut parser = RecordParser::new();
+    let (_, report) = read(filename, options, true, &mut parser)?;
+    Ok((parser.records, report))
+}
+
+/// Collects the raw fields of each row and converts them to `R`. The rows
+/// handed back to the reader are left empty.
+struct RecordParser<R> {
+    /// For each record field, the field of a line it is read from.
+    sources: Vec<Option<usize>>,
+    records: Vec<R>,
+}
+
+impl<R> RecordParser<R> {
+    fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            records: Vec::new(),
+        }
+    }
+}
+
+impl<R: CsvRecord> FieldParser<Option<String>> for RecordParser<R> {
+    fn layout(&mut self, header: Vec<String>, options: &CsvReadOptions) -> Result<Layout> {
+        let (header, keep) = header::dedup_header(header, options.duplicate_headers)?;
+        let columns = R::columns();
+
+        let missing: Vec<String> = columns
+            .iter()
+            .filter(|c| c.required && !header.iter().any(|name| name == c.name))
+            .map(|c| c.name.into())
+            .collect();
+        if !missing.is_empty() {
+            return Err(CsvError::HeaderMismatch(CsvHeaderDiff {
+                missing,
+                unexpected: Vec::new(),
+                reordered: Vec::new(),
+            }));
+        }
+
+        // Columns the record doesn't have are not parsed at all.
+        let mut kept = header.iter();
+        let slots: Vec<Option<usize>> = keep
+            .iter()
+            .map(|&keep| {
+                if !keep {
+                    return None;
+                }
+                let name = kept.next()?;
+                columns.iter().position(|c| c.name == name)
+            })
+            .collect();
+        self.sources = (0..columns.len())
+            .map(|i| slots.iter().position(|&slot| slot == Some(i)))
+            .collect();
+        Ok(Layout {
+            header: columns.iter().map(|c| c.name.into()).collect(),
+            slots,
+        })
+    }
+
+    fn parse(&self, text: &str, _line_num: usize, _column: usize) -> Result<Option<String>> {
+        Ok(Some(text.into()))
+    }
+
+    fn finish_row(&mut self, row: &mut Vec<Option<String>>, line_num: usize) -> Vec<CsvError> {
+        let fields: Vec<Option<&str>> = row.iter().map(Option::as_deref).collect();
+        let result = R::from_fields(&fields);
+        match result {
+            Ok(record) => {
+                self.records.push(record);
+                row.clear();
+                Vec::new()
+            }
+            // ERROR 12: field could not be converted for the record.
+            Err(i) => vec![CsvError::CouldNotParseValue(CsvValuePos {
+                line_num,
+                column: self.sources.get(i).copied().flatten().unwrap_or(i),
+                value: fields.get(i).copied().flatten().unwrap_or_default().into(),
+            })],
+        }
+    }
+}
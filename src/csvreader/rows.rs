@@ -0,0 +1,69 @@
+//! Reading tables that come as rows of cells rather than as text, such as
+//! worksheets and query results. The rows are written out as CSV records,
+//! quoted as needed, so that they go through the same parsing and checks
+//! as a file would.
+
+use std::fmt::Display;
+
+use super::{
+    reader::footer_len, CsvData, CsvError, CsvErrorReport, CsvReadOptions, CsvWriter, Engine,
+    FieldParser, Result, Sink,
+};
+
+/// The cells of a row as one CSV record, without a line break.
+pub(super) fn row_line<I>(cells: I) -> Vec<u8>
+where
+    I: IntoIterator,
+    I::Item: Display,
+{
+    let mut writer = CsvWriter::new(Vec::new());
+    // Writing to a `Vec` can't fail.
+    let _ = writer.write_record(cells);
+    let mut line = writer.into_inner();
+    line.pop();
+    line
+}
+
+/// Parses `lines`, made by [`row_line`], as a file. `lines[i]` is numbered
+/// `line_num(i)`, such as its row, which line breaks in a cell don't change.
+pub(super) fn parse_rows<T: Default>(
+    lines: &[Vec<u8>],
+    line_num: impl Fn(usize) -> usize,
+    options: &CsvReadOptions,
+    collect_errors: bool,
+    parser: &mut dyn FieldParser<T>,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let options = CsvReadOptions {
+        delimiter: ',',
+        quote: Some('"'),
+        ..options.clone()
+    };
+    // ERROR 4: there were no rows
+    if lines.is_empty() {
+        return Err(CsvError::FileIsEmpty);
+    }
+    let sink = Sink::new(&options, collect_errors);
+    let more = &lines[1..options.header_lines().clamp(1, lines.len())];
+    let (mut engine, first) = Engine::start(&lines[0], more, sink, parser)?;
+    let mut data = Vec::new();
+    let skip = match first {
+        Some(_) => 0,
+        None => 1 + more.len(),
+    };
+    let end = lines.len() - footer_len(lines[skip..].iter().map(Vec::as_slice), &options);
+    for (i, line) in lines[..end].iter().enumerate().skip(skip) {
+        // The BOM was already taken off the first line.
+        let raw = match first {
+            Some(first) if i == 0 => first,
+            _ => line,
+        };
+        if let Some(row) = engine.record(line_num(i), raw, parser)? {
+            data.push(row);
+        }
+        if engine.done() {
+            break;
+        }
+    }
+    let header = engine.header;
+    Ok((CsvData { header, data }, engine.sink.report))
+}
@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+use super::{stream_csv, CsvData, CsvErrorReport, CsvReadOptions, Result};
+
+/// A uniform random sample of `n` records of `filename`, in file order, or
+/// all of them if there are fewer. The file is streamed once, and only the
+/// sample is kept in memory. The same `seed` picks the same records.
+pub fn read_csv_sample<T: Default + FromStr>(
+    filename: &str,
+    n: usize,
+    seed: u64,
+) -> Result<CsvData<T>> {
+    read_csv_sample_with_options(filename, n, seed, &CsvReadOptions::default())
+        .map(|(data, _)| data)
+}
+
+/// [`read_csv_sample`] with explicit options, see [`stream_csv`].
+pub fn read_csv_sample_with_options<T: Default + FromStr>(
+    filename: &str,
+    n: usize,
+    seed: u64,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let mut records = stream_csv::<T>(filename, options)?;
+    let header = records.header().to_vec();
+    // Reservoir sampling: record `i` replaces a random one of the sample
+    // with probability `n / (i + 1)`.
+    let mut rng = SplitMix64(seed);
+    // `n` may be far more than the file has, so it only bounds the first
+    // allocation.
+    let mut reservoir: Vec<(usize, Vec<T>)> = Vec::with_capacity(n.min(4096));
+    for (i, record) in records.by_ref().enumerate() {
+        let record = record?;
+        if reservoir.len() < n {
+            reservoir.push((i, record));
+        } else {
+            let j = rng.below(i as u64 + 1) as usize;
+            if j < n {
+                reservoir[j] = (i, record);
+            }
+        }
+    }
+    reservoir.sort_by_key(|&(i, _)| i);
+
+    let data = reservoir.into_iter().map(|(_, record)| record).collect();
+    Ok((CsvData { header, data }, records.into_report()))
+}
+
+/// A small, fast generator; good enough for picking records.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`.
+    fn below(&mut self, n: u64) -> u64 {
+        ((self.next() as u128 * n as u128) >> 64) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    fn numbers(count: usize) -> TempFile {
+        let mut contents = String::from("n\n");
+        for i in 0..count {
+            contents.push_str(&format!("{}\n", i));
+        }
+        TempFile::new("sample.csv", &contents)
+    }
+
+    fn sample(file: &TempFile, n: usize, seed: u64) -> Vec<u32> {
+        let data: CsvData<u32> = read_csv_sample(file.path(), n, seed).unwrap();
+        assert_eq!(data.header, ["n"]);
+        data.data.into_iter().map(|record| record[0]).collect()
+    }
+
+    #[test]
+    fn the_same_seed_picks_the_same_records_in_file_order() {
+        let file = numbers(1000);
+        let first = sample(&file, 10, 42);
+        assert_eq!(first.len(), 10);
+        assert!(first.windows(2).all(|w| w[0] < w[1]), "{:?}", first);
+        assert_eq!(sample(&file, 10, 42), first);
+        assert_ne!(sample(&file, 10, 43), first);
+    }
+
+    #[test]
+    fn every_record_can_be_picked() {
+        let file = numbers(20);
+        let mut seen = [false; 20];
+        for seed in 0..200 {
+            for n in sample(&file, 3, seed) {
+                seen[n as usize] = true;
+            }
+        }
+        assert!(seen.iter().all(|&seen| seen), "{:?}", seen);
+    }
+
+    #[test]
+    fn more_than_the_file_has_gives_every_record() {
+        let file = numbers(5);
+        assert_eq!(sample(&file, 5, 1), [0, 1, 2, 3, 4]);
+        assert_eq!(sample(&file, usize::MAX, 1), [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn a_sample_of_none_is_empty() {
+        let file = numbers(5);
+        assert!(sample(&file, 0, 1).is_empty());
+        let (data, report) =
+            read_csv_sample_with_options::<u32>(file.path(), 0, 1, &CsvReadOptions::default())
+                .unwrap();
+        assert!(data.data.is_empty());
+        assert_eq!(report.records, 5);
+    }
+}
@@ -0,0 +1,476 @@
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::fmt;
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+#[cfg(feature = "std")]
+use super::read;
+use super::{
+    header, parse_lines, split_lines, ColumnType, CsvConstraintError, CsvData, CsvDuplicateKey,
+    CsvError, CsvErrorReport, CsvReadOptions, CsvRuleError, CsvSchemaError, FieldParser, Layout,
+    Result, Sink, Value,
+};
+
+/// A rule every non-empty cell of a column has to satisfy.
+#[derive(Debug, Clone)]
+pub enum Constraint {
+    /// Numeric values must be at least this.
+    Min(f64),
+    /// Numeric values must be at most this.
+    Max(f64),
+    /// The text of the cell must match.
+    #[cfg(feature = "regex")]
+    Pattern(Regex),
+    /// The text of the cell must have at most this many characters.
+    MaxLength(usize),
+}
+
+impl Constraint {
+    fn check(&self, text: &str, value: &Value) -> bool {
+        match self {
+            Self::Min(min) => value.as_f64().is_none_or(|x| x >= *min),
+            Self::Max(max) => value.as_f64().is_none_or(|x| x <= *max),
+            #[cfg(feature = "regex")]
+            Self::Pattern(re) => re.is_match(text),
+            Self::MaxLength(len) => text.chars().count() <= *len,
+        }
+    }
+}
+
+impl PartialEq for Constraint {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Min(a), Self::Min(b)) | (Self::Max(a), Self::Max(b)) => a == b,
+            #[cfg(feature = "regex")]
+            (Self::Pattern(a), Self::Pattern(b)) => a.as_str() == b.as_str(),
+            (Self::MaxLength(a), Self::MaxLength(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Min(min) => write!(f, "min {}", min),
+            Self::Max(max) => write!(f, "max {}", max),
+            #[cfg(feature = "regex")]
+            Self::Pattern(re) => write!(f, "pattern {}", re),
+            Self::MaxLength(len) => write!(f, "max length {}", len),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub column_type: ColumnType,
+    /// Whether empty cells are allowed; they are read as [`Value::Null`].
+    pub nullable: bool,
+    /// Whether the file has to have this column.
+    pub required: bool,
+    /// Value of every cell when an optional column is missing from the file.
+    pub default: Value,
+    pub constraints: Vec<Constraint>,
+}
+
+impl ColumnSchema {
+    pub fn new(name: &str, column_type: ColumnType) -> Self {
+        Self {
+            name: name.into(),
+            column_type,
+            nullable: false,
+            required: true,
+            default: Value::Null,
+            constraints: Vec::new(),
+        }
+    }
+
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+
+    /// Allows the column to be missing from the file; it is then read as
+    /// [`Value::Null`].
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    /// Makes the column optional, filled with `default` when it is missing.
+    pub fn or_default(mut self, default: Value) -> Self {
+        self.required = false;
+        self.default = default;
+        self
+    }
+
+    pub fn constraint(mut self, constraint: Constraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    pub fn min(self, min: f64) -> Self {
+        self.constraint(Constraint::Min(min))
+    }
+
+    pub fn max(self, max: f64) -> Self {
+        self.constraint(Constraint::Max(max))
+    }
+
+    #[cfg(feature = "regex")]
+    pub fn pattern(self, pattern: Regex) -> Self {
+        self.constraint(Constraint::Pattern(pattern))
+    }
+
+    pub fn max_length(self, len: usize) -> Self {
+        self.constraint(Constraint::MaxLength(len))
+    }
+}
+
+/// Whether the file's columns have to appear in the order of the schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ColumnOrder {
+    #[default]
+    Strict,
+    /// Columns may appear in any order; the result uses the schema's order.
+    Any,
+}
+
+/// A parsed record, with its values looked up by column name.
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a> {
+    pub columns: &'a [ColumnSchema],
+    pub values: &'a [Value],
+}
+
+impl<'a> Row<'a> {
+    pub fn get(&self, name: &str) -> Option<&'a Value> {
+        let i = self.columns.iter().position(|c| c.name == name)?;
+        self.values.get(i)
+    }
+}
+
+/// A named check on a whole record, for conditions spanning several
+/// columns such as `end >= start`.
+#[derive(Clone)]
+pub struct Rule {
+    pub name: String,
+    check: Arc<dyn Fn(&Row) -> bool + Send + Sync>,
+}
+
+impl Rule {
+    pub fn new(name: &str, check: impl Fn(&Row) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            name: name.into(),
+            check: Arc::new(check),
+        }
+    }
+
+    pub fn check(&self, row: &Row) -> bool {
+        (self.check)(row)
+    }
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Rule").field(&self.name).finish()
+    }
+}
+
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && Arc::ptr_eq(&self.check, &other.check)
+    }
+}
+
+/// The columns a file is expected to have, with their types.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvSchema {
+    pub columns: Vec<ColumnSchema>,
+    pub order: ColumnOrder,
+    /// Sets of columns whose combined values may not repeat. Records with a
+    /// null in the key are not checked, names not in the schema are ignored.
+    pub unique_keys: Vec<Vec<String>>,
+    /// Checked on every record whose fields all parsed.
+    pub rules: Vec<Rule>,
+}
+
+impl CsvSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn column(mut self, column: ColumnSchema) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    pub fn order(mut self, order: ColumnOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Makes `columns` a unique key, e.g. `.unique(&["id"])` or
+    /// `.unique(&["region", "code"])`.
+    pub fn unique(mut self, columns: &[&str]) -> Self {
+        self.unique_keys
+            .push(columns.iter().map(|c| c.to_string()).collect());
+        self
+    }
+
+    /// Adds a row rule. Records it returns `false` for are reported under
+    /// `name`.
+    pub fn rule(
+        mut self,
+        name: &str,
+        check: impl Fn(&Row) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push(Rule::new(name, check));
+        self
+    }
+}
+
+/// One column per line, e.g. `id: int, min 1, max 10`, followed by the
+/// unique keys and rules.
+impl fmt::Display for CsvSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for column in &self.columns {
+            write!(f, "{}: {}", column.name, column.column_type)?;
+            if column.nullable {
+                write!(f, ", nullable")?;
+            }
+            if !column.required {
+                write!(f, ", optional (default {:?})", column.default.to_string())?;
+            }
+            for constraint in &column.constraints {
+                write!(f, ", {}", constraint)?;
+            }
+            writeln!(f)?;
+        }
+        for key in &self.unique_keys {
+            writeln!(f, "unique: {}", key.join(", "))?;
+        }
+        for rule in &self.rules {
+            writeln!(f, "rule: {}", rule.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses CSV text that is already in memory against `schema`, like
+/// [`read_csv_with_schema_options`]. Needs neither a file system nor `std`.
+pub fn parse_csv_with_schema(
+    input: &[u8],
+    schema: &CsvSchema,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<Value>, CsvErrorReport)> {
+    let sink = Sink::new(options, false);
+    parse_lines(
+        &split_lines(input, options),
+        sink,
+        &mut SchemaParser::new(schema),
+    )
+}
+
+/// Reads `filename`, checking its header against `schema` and parsing each
+/// column to its declared type.
+#[cfg(feature = "std")]
+pub fn read_csv_with_schema(filename: &str, schema: &CsvSchema) -> Result<CsvData<Value>> {
+    read_csv_with_schema_options(filename, schema, &CsvReadOptions::default()).map(|(data, _)| data)
+}
+
+/// [`read_csv_with_schema`] with explicit options, see
+/// [`read_csv_with_options`](super::read_csv_with_options).
+#[cfg(feature = "std")]
+pub fn read_csv_with_schema_options(
+    filename: &str,
+    schema: &CsvSchema,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<Value>, CsvErrorReport)> {
+    read(filename, options, false, &mut SchemaParser::new(schema))
+}
+
+/// [`read_csv_with_schema`] in collect-all mode, see
+/// [`read_csv_validated`](super::read_csv_validated).
+#[cfg(feature = "std")]
+pub fn read_csv_validated_with_schema(
+    filename: &str,
+    schema: &CsvSchema,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<Value>, CsvErrorReport)> {
+    read(filename, options, true, &mut SchemaParser::new(schema))
+}
+
+struct SchemaParser<'a> {
+    schema: &'a CsvSchema,
+    /// For each field of a record, its column in the schema.
+    columns: Vec<Option<usize>>,
+    /// Optional schema columns the file doesn't have.
+    missing: Vec<usize>,
+    keys: Vec<UniqueKey>,
+}
+
+/// A unique key's schema columns, and the line each of its values was
+/// first seen on.
+struct UniqueKey {
+    columns: Vec<usize>,
+    seen: BTreeMap<Vec<String>, usize>,
+}
+
+impl<'a> SchemaParser<'a> {
+    fn new(schema: &'a CsvSchema) -> Self {
+        Self {
+            schema,
+            columns: Vec::new(),
+            missing: Vec::new(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl FieldParser<Value> for SchemaParser<'_> {
+    fn layout(&mut self, header: Vec<String>, options: &CsvReadOptions) -> Result<Layout> {
+        let (header, keep) = header::dedup_header(header, options.duplicate_headers)?;
+        let names: Vec<String> = self.schema.columns.iter().map(|c| c.name.clone()).collect();
+        // Optional columns only count when the file has them.
+        let expected: Vec<String> = self
+            .schema
+            .columns
+            .iter()
+            .filter(|c| c.required || header.contains(&c.name))
+            .map(|c| c.name.clone())
+            .collect();
+        let any_order = self.schema.order == ColumnOrder::Any;
+        header::check_header(&header, &expected, any_order)?;
+        self.missing = (0..names.len())
+            .filter(|&i| !header.contains(&names[i]))
+            .collect();
+
+        let mut kept = header.iter();
+        self.columns = keep
+            .iter()
+            .map(|&keep| {
+                if !keep {
+                    return None;
+                }
+                let name = kept.next()?;
+                names.iter().position(|n| n == name)
+            })
+            .collect();
+        self.keys = self
+            .schema
+            .unique_keys
+            .iter()
+            .map(|key| UniqueKey {
+                columns: key
+                    .iter()
+                    .filter_map(|name| names.iter().position(|n| n == name))
+                    .collect(),
+                seen: BTreeMap::new(),
+            })
+            .collect();
+        Ok(Layout {
+            header: names,
+            slots: self.columns.clone(),
+        })
+    }
+
+    fn parse(&self, text: &str, line_num: usize, column: usize) -> Result<Value> {
+        let schema = &self.schema.columns[self.columns[column].unwrap_or_default()];
+        let error = || CsvSchemaError {
+            line_num,
+            column,
+            name: schema.name.clone(),
+            expected: schema.column_type,
+            value: text.into(),
+        };
+        if text.is_empty() {
+            return match schema.nullable {
+                true => Ok(Value::Null),
+                // ERROR 11: empty value in a column that is not nullable.
+                false => Err(CsvError::NotNullable(error())),
+            };
+        }
+        // ERROR 10: value does not have the column's type.
+        let value = schema
+            .column_type
+            .parse(text)
+            .ok_or_else(|| CsvError::TypeMismatch(error()))?;
+        // ERROR 13: value breaks one of the column's constraints.
+        if let Some(constraint) = schema.constraints.iter().find(|c| !c.check(text, &value)) {
+            return Err(CsvError::ConstraintViolation(CsvConstraintError {
+                line_num,
+                column,
+                name: schema.name.clone(),
+                value: text.into(),
+                constraint: constraint.clone(),
+            }));
+        }
+        Ok(value)
+    }
+
+    fn finish_row(&mut self, row: &mut Vec<Value>, line_num: usize) -> Vec<CsvError> {
+        for &i in &self.missing {
+            row[i] = self.schema.columns[i].default.clone();
+        }
+
+        let record = Row {
+            columns: &self.schema.columns,
+            values: row,
+        };
+        // ERROR 15: record fails a row rule.
+        let mut errors: Vec<CsvError> = self
+            .schema
+            .rules
+            .iter()
+            .filter(|rule| !rule.check(&record))
+            .map(|rule| {
+                CsvError::RuleViolation(CsvRuleError {
+                    line_num,
+                    rule: rule.name.clone(),
+                })
+            })
+            .collect();
+
+        // Only a row that is kept claims its keys.
+        let mut claimed = Vec::new();
+        for (k, key) in self.keys.iter().enumerate() {
+            if key.columns.is_empty() || key.columns.iter().any(|&i| row[i].is_null()) {
+                continue;
+            }
+            let values: Vec<String> = key.columns.iter().map(|&i| row[i].to_string()).collect();
+            match key.seen.get(&values) {
+                // ERROR 14: key already used by an earlier record.
+                Some(&first_line) => errors.push(CsvError::DuplicateKey(CsvDuplicateKey {
+                    line_num,
+                    first_line,
+                    columns: key
+                        .columns
+                        .iter()
+                        .map(|&i| self.schema.columns[i].name.clone())
+                        .collect(),
+                    values,
+                })),
+                None => claimed.push((k, values)),
+            }
+        }
+        if errors.is_empty() {
+            for (k, values) in claimed {
+                self.keys[k].seen.insert(values, line_num);
+            }
+        }
+        errors
+    }
+}
@@ -0,0 +1,41 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::{CsvError, Value};
+
+/// Every error serializes to the same shape, with `null` for the position
+/// fields that don't apply:
+///
+/// ```json
+/// { "code": "could_not_parse_value", "category": "parse", "severity": "error",
+///   "message": "line 4, field 2: could not parse value \"x\"",
+///   "path": null, "line": 4, "column": 1, "value": "x" }
+/// ```
+///
+/// `line` is 1-based, `column` is the 0-based field index.
+impl Serialize for CsvError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("CsvError", 8)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("category", &self.category())?;
+        s.serialize_field("severity", &self.severity())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.serialize_field("path", &self.path())?;
+        s.serialize_field("line", &self.line_num())?;
+        s.serialize_field("column", &self.column())?;
+        s.serialize_field("value", &self.value())?;
+        s.end()
+    }
+}
+
+/// Values serialize as the matching JSON scalar, `Null` as `null`.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Null => serializer.serialize_none(),
+            Self::Bool(b) => serializer.serialize_bool(*b),
+            Self::Int(i) => serializer.serialize_i64(*i),
+            Self::Float(x) => serializer.serialize_f64(*x),
+            Self::Text(s) => serializer.serialize_str(s),
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{hash_map::RandomState, BinaryHeap},
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    hash::{BuildHasher, Hasher},
+    io::{self, BufWriter, Write},
+    mem,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+};
+
+use super::{
+    stream_csv, CsvData, CsvError, CsvErrorReport, CsvFileError, CsvReadOptions, CsvRecords,
+    CsvWriter, Result, Strictness,
+};
+
+/// Numbers the spill files of sorts running at the same time.
+static SORTS: AtomicUsize = AtomicUsize::new(0);
+
+/// How [`CsvData::sort_by_column`] and [`sort_csv_file`] compare values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOptions {
+    pub descending: bool,
+    /// Compare as numbers rather than text. Values that are not numbers
+    /// come after all that are, also in a descending sort.
+    pub numeric: bool,
+    /// Roughly how many bytes of records [`sort_csv_file`] holds in memory
+    /// before it spills them to a temporary file. 256 MiB by default.
+    pub memory_limit: usize,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            descending: false,
+            numeric: false,
+            memory_limit: 256 << 20,
+        }
+    }
+}
+
+impl SortOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
+    pub fn numeric(mut self, numeric: bool) -> Self {
+        self.numeric = numeric;
+        self
+    }
+
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    fn compare(&self, a: &SortKey, b: &SortKey) -> Ordering {
+        // Text comes after numbers in either direction.
+        let is_text = |key: &SortKey| matches!(key, SortKey::Text(_));
+        is_text(a)
+            .cmp(&is_text(b))
+            .then_with(|| match self.descending {
+                true => b.cmp(a),
+                false => a.cmp(b),
+            })
+    }
+}
+
+impl<T: Display> CsvData<T> {
+    /// Sorts the records by `column`, keeping the order of equal ones. A
+    /// record too short to have the column sorts as if it were empty.
+    pub fn sort_by_column(&mut self, column: &str, options: SortOptions) -> Result<()> {
+        let i = self
+            .header
+            .iter()
+            .position(|name| name == column)
+            .ok_or_else(|| CsvError::UnknownColumn(column.into()))?;
+        let mut keyed: Vec<_> = self
+            .data
+            .drain(..)
+            .map(|row| (SortKey::of(&row, i, options.numeric), row))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| options.compare(a, b));
+        self.data = keyed.into_iter().map(|(_, row)| row).collect();
+        Ok(())
+    }
+}
+
+/// Sorts the file `input` by `key_column` into `output`, as text, without
+/// holding more of it in memory than fits: see
+/// [`sort_csv_file_with_options`].
+pub fn sort_csv_file(input: &str, output: &str, key_column: &str) -> Result<()> {
+    let options = CsvReadOptions::default();
+    sort_csv_file_with_options(input, output, key_column, &options, SortOptions::default())
+        .map(|_| ())
+}
+
+/// Like [`sort_csv_file`], but with explicit options. Records are read in
+/// chunks of about `order.memory_limit` bytes, each chunk is sorted and
+/// written to a temporary file, and the chunks are then merged into
+/// `output`. A file that fits in one chunk is sorted in memory. Equal
+/// records keep their order.
+///
+/// The output is written with `options.delimiter`, and with a header only
+/// if the input has one. Problems with the input are handled as by
+/// [`stream_csv`]; those recovered from are returned.
+pub fn sort_csv_file_with_options(
+    input: &str,
+    output: &str,
+    key_column: &str,
+    options: &CsvReadOptions,
+    order: SortOptions,
+) -> Result<CsvErrorReport> {
+    let mut records = stream_csv::<String>(input, options)?;
+    let header = records.header().to_vec();
+    let column = header
+        .iter()
+        .position(|name| name == key_column)
+        .ok_or_else(|| CsvError::UnknownColumn(key_column.into()))?;
+
+    let mut spills = Spills::new();
+    let mut chunk = Vec::new();
+    let mut chunk_bytes = 0;
+    for record in &mut records {
+        let record = record?;
+        chunk_bytes += record_size(&record);
+        chunk.push((SortKey::of(&record, column, order.numeric), record));
+        if chunk_bytes >= order.memory_limit {
+            chunk.sort_by(|(a, _), (b, _)| order.compare(a, b));
+            spills.write(mem::take(&mut chunk))?;
+            chunk_bytes = 0;
+        }
+    }
+    let report = records.into_report();
+    chunk.sort_by(|(a, _), (b, _)| order.compare(a, b));
+
+    let path = Path::new(output);
+    // ERROR 1: output could not be created.
+    let file = File::create(path).map_err(|e| CsvFileError::into_error(path, e))?;
+    // ERROR 20: output could not be written.
+    let write_error = |source| {
+        CsvError::CouldNotWriteFile(CsvFileError {
+            path: path.to_path_buf(),
+            source,
+        })
+    };
+    let mut writer = CsvWriter::new(BufWriter::new(file)).delimiter(options.delimiter);
+    if options.has_header {
+        writer.write_record(&header).map_err(write_error)?;
+    }
+    if spills.paths.is_empty() {
+        for (_, record) in &chunk {
+            writer.write_record(record).map_err(write_error)?;
+        }
+    } else {
+        spills.write(chunk)?;
+        spills.merge(&mut writer, column, order, write_error)?;
+    }
+    writer.into_inner().flush().map_err(write_error)?;
+    Ok(report)
+}
+
+/// An estimate of the memory a record takes.
+fn record_size(record: &[String]) -> usize {
+    record
+        .iter()
+        .map(|field| field.len() + mem::size_of::<String>())
+        .sum()
+}
+
+/// The sorted chunks of a [`sort_csv_file`], removed again once it is done.
+struct Spills {
+    dir: PathBuf,
+    prefix: String,
+    paths: Vec<PathBuf>,
+}
+
+impl Spills {
+    fn new() -> Self {
+        let sort = SORTS.fetch_add(1, AtomicOrdering::Relaxed);
+        // Random, so that no other user can guess the names and put files
+        // or links there first.
+        let random = RandomState::new().build_hasher().finish();
+        Self {
+            dir: std::env::temp_dir(),
+            prefix: format!("csvsort-{}-{}-{:016x}", process::id(), sort, random),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Writes a sorted chunk to a new file, unless it is empty.
+    fn write(&mut self, chunk: Vec<(SortKey, Vec<String>)>) -> Result<()> {
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        let path = self
+            .dir
+            .join(format!("{}-{}.csv", self.prefix, self.paths.len()));
+        // ERROR 1: spill file could not be created, or already exists.
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| CsvFileError::into_error(&path, e))?;
+        self.paths.push(path.clone());
+        let mut writer = CsvWriter::new(BufWriter::new(file));
+        chunk
+            .iter()
+            .try_for_each(|(_, record)| writer.write_record(record))
+            .and_then(|_| writer.into_inner().flush())
+            // ERROR 20: spill file could not be written.
+            .map_err(|source| CsvError::CouldNotWriteFile(CsvFileError { path, source }))
+    }
+
+    /// Merges the chunks into `writer`, taking the smallest head of all of
+    /// them each time. Ties go to the earlier chunk, which keeps the sort
+    /// stable.
+    fn merge<W: Write>(
+        &self,
+        writer: &mut CsvWriter<W>,
+        column: usize,
+        order: SortOptions,
+        write_error: impl Fn(io::Error) -> CsvError,
+    ) -> Result<()> {
+        // Chunks hold records, not a header, and are written with the
+        // default delimiter and quote.
+        let options = CsvReadOptions::new()
+            .has_header(false)
+            .strictness(Strictness::Lenient);
+        let mut chunks = self
+            .paths
+            .iter()
+            .map(|path| stream_csv::<String>(&path.to_string_lossy(), &options))
+            .collect::<Result<Vec<_>>>()?;
+        let next = |chunks: &mut [CsvRecords<'_, String>], i: usize| -> Result<_> {
+            let record = chunks[i].next().transpose()?;
+            Ok(record.map(|record| {
+                let key = SortKey::of(&record, column, order.numeric);
+                Reverse(Head {
+                    key,
+                    chunk: i,
+                    record,
+                    order,
+                })
+            }))
+        };
+        let mut heads = BinaryHeap::new();
+        for i in 0..chunks.len() {
+            heads.extend(next(&mut chunks, i)?);
+        }
+        while let Some(Reverse(head)) = heads.pop() {
+            writer.write_record(&head.record).map_err(&write_error)?;
+            heads.extend(next(&mut chunks, head.chunk)?);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Spills {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// The next record of one chunk, ordered for the merge.
+struct Head {
+    key: SortKey,
+    chunk: usize,
+    record: Vec<String>,
+    order: SortOptions,
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order
+            .compare(&self.key, &other.key)
+            .then(self.chunk.cmp(&other.chunk))
+    }
+}
+
+/// A value's text, or the number it stands for.
+#[derive(Debug, PartialEq)]
+pub(super) enum SortKey {
+    Number(f64),
+    Text(String),
+}
+
+impl SortKey {
+    pub(super) fn new(value: &impl Display, numeric: bool) -> Self {
+        let text = value.to_string();
+        match numeric {
+            true => text.trim().parse().map_or(Self::Text(text), Self::Number),
+            false => Self::Text(text),
+        }
+    }
+
+    /// The key of column `i` of `row`, empty text if the record is too
+    /// short to have it.
+    pub(super) fn of(row: &[impl Display], i: usize, numeric: bool) -> Self {
+        row.get(i)
+            .map_or(Self::Text(String::new()), |value| Self::new(value, numeric))
+    }
+}
+
+impl Eq for SortKey {}
+
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Text(a), Self::Text(b)) => a.cmp(b),
+            (Self::Number(_), Self::Text(_)) => Ordering::Less,
+            (Self::Text(_), Self::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csvreader::{read_csv, testutil::TempFile};
+
+    /// Keys with many duplicates, so that stability shows, some of them not
+    /// numbers.
+    fn unsorted() -> String {
+        let mut contents = String::from("key,seq\n");
+        for i in 0..200 {
+            let key = match i % 7 {
+                0 => "n/a".to_string(),
+                1 => format!("{}.5", i % 5),
+                _ => (i * 37 % 11).to_string(),
+            };
+            contents.push_str(&format!("{},{}\n", key, i));
+        }
+        contents
+    }
+
+    /// The file sorted through spill files, and in memory.
+    fn both(order: SortOptions) -> (CsvData<String>, CsvData<String>) {
+        let input = TempFile::new("sort-in.csv", &unsorted());
+        let output = TempFile::empty("sort-out.csv");
+        let options = CsvReadOptions::default();
+        // A few records per spill file.
+        let order = order.memory_limit(64);
+        sort_csv_file_with_options(input.path(), output.path(), "key", &options, order).unwrap();
+        let external = read_csv::<String>(output.path()).unwrap();
+
+        let mut in_memory = read_csv::<String>(input.path()).unwrap();
+        in_memory.sort_by_column("key", order).unwrap();
+        (external, in_memory)
+    }
+
+    #[test]
+    fn spilled_sort_matches_the_in_memory_one() {
+        let (external, in_memory) = both(SortOptions::new());
+        assert_eq!(external.header, in_memory.header);
+        assert_eq!(external.data, in_memory.data);
+    }
+
+    #[test]
+    fn equal_keys_keep_their_order() {
+        let (external, _) = both(SortOptions::new());
+        for pair in external.data.windows(2) {
+            if pair[0][0] == pair[1][0] {
+                let seq = |row: &Vec<String>| row[1].parse::<usize>().unwrap();
+                assert!(seq(&pair[0]) < seq(&pair[1]), "{:?}", pair);
+            }
+        }
+    }
+
+    #[test]
+    fn descending_numeric_matches_and_keeps_text_last() {
+        let (external, in_memory) = both(SortOptions::new().numeric(true).descending(true));
+        assert_eq!(external.data, in_memory.data);
+        let keys: Vec<&str> = external.data.iter().map(|row| row[0].as_str()).collect();
+        let numbers: Vec<f64> = keys.iter().map_while(|k| k.parse().ok()).collect();
+        assert!(numbers.windows(2).all(|w| w[0] >= w[1]));
+        assert!(keys[numbers.len()..].iter().all(|&k| k == "n/a"));
+        assert_eq!(keys.len() - numbers.len(), 200 / 7 + 1);
+    }
+
+    #[test]
+    fn numeric_sorts_by_value_not_text() {
+        let (external, in_memory) = both(SortOptions::new().numeric(true));
+        assert_eq!(external.data, in_memory.data);
+        let keys: Vec<&str> = external.data.iter().map(|row| row[0].as_str()).collect();
+        let numbers: Vec<f64> = keys.iter().map_while(|k| k.parse().ok()).collect();
+        assert!(numbers.windows(2).all(|w| w[0] <= w[1]));
+        assert!(keys[numbers.len()..].iter().all(|&k| k == "n/a"));
+    }
+
+    #[test]
+    fn small_inputs_are_sorted_without_spilling() {
+        let input = TempFile::new("sort-small.csv", "k\nb\na\nc\n");
+        let output = TempFile::empty("sort-small-out.csv");
+        sort_csv_file(input.path(), output.path(), "k").unwrap();
+        assert_eq!(output.read(), "k\na\nb\nc\n");
+    }
+
+    #[test]
+    fn short_records_sort_as_empty() {
+        let rows: [&[&str]; 4] = [&["b", "1"], &[], &["a", "2"], &["3", "3"]];
+        let mut data = CsvData {
+            header: vec!["k".to_string(), "v".to_string()],
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        };
+        data.sort_by_column("k", SortOptions::new()).unwrap();
+        let empty: Vec<String> = Vec::new();
+        assert_eq!(data.data[0], empty);
+        assert_eq!(data.data[1], ["3", "3"]);
+        data.sort_by_column("k", SortOptions::new().numeric(true))
+            .unwrap();
+        assert_eq!(data.data[0], ["3", "3"]);
+        assert_eq!(data.data[1], empty);
+        assert_eq!(data.data[2], ["a", "2"]);
+    }
+
+    #[test]
+    fn files_without_a_header_are_sorted_without_one() {
+        let input = TempFile::new("sort-nh.csv", "3,c\n1,a\n2,b\n1,z\n");
+        let options = CsvReadOptions::new().has_header(false);
+        for limit in [1 << 20, 8] {
+            let output = TempFile::empty("sort-nh-out.csv");
+            let order = SortOptions::new().memory_limit(limit);
+            sort_csv_file_with_options(input.path(), output.path(), "column_1", &options, order)
+                .unwrap();
+            assert_eq!(output.read(), "1,a\n1,z\n2,b\n3,c\n");
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::{borrow::Cow, io, path::Path, str::FromStr};
+
+use rusqlite::{types::ValueRef, Connection, OpenFlags};
+
+use super::{
+    file::open_file,
+    rows::{parse_rows, row_line},
+    CsvData, CsvError, CsvErrorReport, CsvFileError, CsvQueryError, CsvReadOptions, FromStrParser,
+    Result,
+};
+
+impl<T: Default + FromStr> CsvData<T> {
+    /// Runs `sql` on `conn` and reads the rows it returns as records, named
+    /// by its columns. Each value is parsed from its text, as in a file, so
+    /// that a table and a CSV export of it read the same: `NULL` as an
+    /// empty field, a real as `1.0` even when it is whole.
+    ///
+    /// Problems are reported with line 1 for the column names and line 2
+    /// for the first row.
+    pub fn from_sqlite_query(conn: &Connection, sql: &str) -> Result<Self> {
+        Self::from_sqlite_query_with_options(conn, sql, &CsvReadOptions::default())
+            .map(|(data, _)| data)
+    }
+
+    /// [`from_sqlite_query`](CsvData::from_sqlite_query) with explicit
+    /// options, see [`read_csv_with_options`](super::read_csv_with_options).
+    /// The delimiter, quote and header options don't apply.
+    pub fn from_sqlite_query_with_options(
+        conn: &Connection,
+        sql: &str,
+        options: &CsvReadOptions,
+    ) -> Result<(Self, CsvErrorReport)> {
+        let lines = query_lines(conn, sql)?;
+        let options = options.clone().has_header(true).header_rows(1);
+        parse_rows(&lines, |i| i + 1, &options, false, &mut FromStrParser)
+    }
+
+    /// [`from_sqlite_query`](CsvData::from_sqlite_query) on the database at
+    /// `path`, opened read-only.
+    pub fn from_sqlite_file(path: &str, sql: &str) -> Result<Self> {
+        open_file(path)?;
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            // ERROR 1: file could not be opened as a database.
+            .map_err(|e| {
+                CsvError::CouldNotOpenFile(CsvFileError {
+                    path: Path::new(path).to_path_buf(),
+                    source: io::Error::other(e),
+                })
+            })?;
+        Self::from_sqlite_query(&conn, sql)
+    }
+}
+
+/// The column names and rows of the result of `sql`, as CSV records.
+fn query_lines(conn: &Connection, sql: &str) -> Result<Vec<Vec<u8>>> {
+    let failed = |e: rusqlite::Error| {
+        CsvError::QueryFailed(CsvQueryError {
+            query: sql.into(),
+            reason: e.to_string(),
+        })
+    };
+    // ERROR 19: query could not be run, or a row could not be read.
+    let mut statement = conn.prepare(sql).map_err(failed)?;
+    let columns = statement.column_count();
+    let mut lines = vec![row_line(statement.column_names())];
+    let mut rows = statement.query([]).map_err(failed)?;
+    while let Some(row) = rows.next().map_err(failed)? {
+        let cells = (0..columns)
+            .map(|i| row.get_ref(i).map(cell_text))
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(failed)?;
+        lines.push(row_line(cells));
+    }
+    Ok(lines)
+}
+
+/// The text of a value, as it would be written to a CSV file.
+fn cell_text(value: ValueRef<'_>) -> Cow<'_, str> {
+    match value {
+        ValueRef::Null => Cow::Borrowed(""),
+        ValueRef::Integer(i) => Cow::Owned(i.to_string()),
+        // `{:?}` keeps the `.0` of a whole number, so it reads back as one.
+        ValueRef::Real(x) => Cow::Owned(format!("{:?}", x)),
+        ValueRef::Text(text) | ValueRef::Blob(text) => String::from_utf8_lossy(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn people() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE people (id INTEGER, \"first name\" TEXT, \"a,\"\"b\"\"\" TEXT, score REAL);
+             INSERT INTO people VALUES (1, 'Ada', 'x,\"y\"', 2.0);
+             INSERT INTO people VALUES (2, 'line
+break', NULL, 0.5);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn rows_are_read_as_records_named_by_their_columns() {
+        let data: CsvData<String> =
+            CsvData::from_sqlite_query(&people(), "SELECT * FROM people ORDER BY id").unwrap();
+        assert_eq!(data.header, ["id", "first name", "a,\"b\"", "score"]);
+        assert_eq!(
+            data.data,
+            [
+                ["1", "Ada", "x,\"y\"", "2.0"],
+                ["2", "line\nbreak", "", "0.5"],
+            ]
+        );
+    }
+
+    #[test]
+    fn values_are_parsed_from_their_text() {
+        let data: CsvData<f64> =
+            CsvData::from_sqlite_query(&people(), "SELECT id, score FROM people ORDER BY id")
+                .unwrap();
+        assert_eq!(data.data, [[1.0, 2.0], [2.0, 0.5]]);
+
+        match CsvData::<i64>::from_sqlite_query(&people(), "SELECT score FROM people ORDER BY id") {
+            Err(CsvError::CouldNotParseValue(e)) => {
+                assert_eq!((e.line_num, e.value.as_str()), (2, "2.0"))
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_failing_query_names_it() {
+        match CsvData::<String>::from_sqlite_query(&people(), "SELECT nope FROM people") {
+            Err(CsvError::QueryFailed(e)) => {
+                assert_eq!(e.query, "SELECT nope FROM people");
+                assert!(e.reason.contains("nope"), "{}", e.reason);
+            }
+            other => panic!("expected a query error, got {:?}", other),
+        }
+    }
+}
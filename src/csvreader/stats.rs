@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use super::{ColumnType, CsvData, Value};
+
+/// Summary of one column, see [`CsvData::describe`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ColumnStats {
+    pub name: String,
+    /// The narrowest type all values fit, `Text` for an all-null column.
+    pub column_type: ColumnType,
+    /// Number of non-null values.
+    pub count: usize,
+    /// Number of nulls, counting the cells short records don't have.
+    pub missing: usize,
+    /// Only set for numeric columns.
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    /// Number of distinct values, only set for text columns.
+    pub distinct: Option<usize>,
+}
+
+impl CsvData<Value> {
+    /// One [`ColumnStats`] per column, in header order.
+    pub fn describe(&self) -> Vec<ColumnStats> {
+        (0..self.header.len())
+            .map(|i| {
+                let values = self
+                    .data
+                    .iter()
+                    .map(|row| row.get(i).unwrap_or(&Value::Null));
+                describe_column(&self.header[i], values)
+            })
+            .collect()
+    }
+}
+
+fn describe_column<'a>(name: &str, values: impl Iterator<Item = &'a Value>) -> ColumnStats {
+    let values: Vec<&Value> = values.collect();
+    let column_type = values
+        .iter()
+        .filter_map(|v| v.column_type())
+        .reduce(ColumnType::widen)
+        .unwrap_or(ColumnType::Text);
+    let count = values.iter().filter(|v| !v.is_null()).count();
+    let mut stats = ColumnStats {
+        name: name.into(),
+        column_type,
+        count,
+        missing: values.len() - count,
+        min: None,
+        max: None,
+        mean: None,
+        distinct: None,
+    };
+    match column_type {
+        ColumnType::Int | ColumnType::Float => {
+            let numbers: Vec<f64> = values.iter().filter_map(|v| v.as_f64()).collect();
+            stats.min = numbers.iter().copied().reduce(f64::min);
+            stats.max = numbers.iter().copied().reduce(f64::max);
+            if !numbers.is_empty() {
+                stats.mean = Some(numbers.iter().sum::<f64>() / numbers.len() as f64);
+            }
+        }
+        ColumnType::Text => {
+            let distinct: HashSet<String> = values
+                .iter()
+                .filter(|v| !v.is_null())
+                .map(|v| v.to_string())
+                .collect();
+            stats.distinct = Some(distinct.len());
+        }
+        ColumnType::Bool => {}
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> CsvData<Value> {
+        let text = |s: &str| Value::Text(s.into());
+        CsvData {
+            header: vec!["n".into(), "s".into()],
+            data: vec![
+                vec![Value::Int(4), text("a")],
+                vec![Value::Null, text("b")],
+                vec![Value::Int(-2), text("a")],
+                vec![Value::Int(10)],
+            ],
+        }
+    }
+
+    #[test]
+    fn describes_a_number_column() {
+        let stats = table().describe();
+        assert_eq!(
+            stats[0],
+            ColumnStats {
+                name: "n".into(),
+                column_type: ColumnType::Int,
+                count: 3,
+                missing: 1,
+                min: Some(-2.0),
+                max: Some(10.0),
+                mean: Some(4.0),
+                distinct: None,
+            }
+        );
+    }
+
+    #[test]
+    fn describes_a_text_column() {
+        let stats = table().describe();
+        assert_eq!(
+            stats[1],
+            ColumnStats {
+                name: "s".into(),
+                column_type: ColumnType::Text,
+                count: 3,
+                missing: 1,
+                min: None,
+                max: None,
+                mean: None,
+                distinct: Some(2),
+            }
+        );
+    }
+
+    #[test]
+    fn mixed_numbers_widen_to_float() {
+        let data = CsvData {
+            header: vec!["x".into()],
+            data: vec![vec![Value::Int(1)], vec![Value::Float(2.5)]],
+        };
+        let stats = data.describe();
+        assert_eq!(stats[0].column_type, ColumnType::Float);
+        assert_eq!(stats[0].mean, Some(1.75));
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt::{self, Display};
+
+use super::CsvData;
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// An aligned rendering of a [`CsvData`], see [`CsvData::table`].
+#[derive(Debug)]
+pub struct Table<'a, T> {
+    data: &'a CsvData<T>,
+    header: Option<Vec<String>>,
+    max_rows: Option<usize>,
+    color: bool,
+}
+
+impl<T> CsvData<T> {
+    /// The data as an aligned table, the header underlined:
+    ///
+    /// ```text
+    /// id  name   total
+    /// --  -----  -----
+    /// 1   Alice  12.5
+    /// 2   Bob    7
+    /// ```
+    ///
+    /// This is also what `Display` prints.
+    pub fn table(&self) -> Table<'_, T> {
+        Table {
+            data: self,
+            header: None,
+            max_rows: None,
+            color: false,
+        }
+    }
+}
+
+impl<T> Table<'_, T> {
+    /// Shows these labels instead of the column names.
+    pub fn header(mut self, labels: Vec<String>) -> Self {
+        self.header = Some(labels);
+        self
+    }
+
+    /// Shows only the first `max_rows` records, followed by a line counting
+    /// the rest.
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Highlights the header with ANSI escape codes.
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl<T: Display> Display for Table<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = self.header.as_ref().unwrap_or(&self.data.header);
+        let shown = self
+            .max_rows
+            .unwrap_or(usize::MAX)
+            .min(self.data.data.len());
+        let rows: Vec<Vec<String>> = self.data.data[..shown]
+            .iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect();
+        let widths: Vec<usize> = header
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                rows.iter()
+                    .filter_map(|row| row.get(i))
+                    .chain([name])
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let style = |code: &'static str| if self.color { code } else { "" };
+        let line = |f: &mut fmt::Formatter<'_>, cells: &[String], code: &str| {
+            let cells: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:<width$}", cell))
+                .collect();
+            let reset = if code.is_empty() { "" } else { RESET };
+            writeln!(f, "{}{}{}", code, cells.join("  ").trim_end(), reset)
+        };
+        line(f, header, style(BOLD))?;
+        let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+        line(f, &rule, style(DIM))?;
+        for row in &rows {
+            line(f, row, "")?;
+        }
+        let hidden = self.data.data.len() - shown;
+        if hidden > 0 {
+            writeln!(
+                f,
+                "{}... {} more record(s){}",
+                style(DIM),
+                hidden,
+                style(RESET)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Same as [`CsvData::table`].
+impl<T: Display> Display for CsvData<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.table().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(header: &[&str], rows: &[&[&str]]) -> CsvData<String> {
+        CsvData {
+            header: header.iter().map(|name| name.to_string()).collect(),
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn columns_are_as_wide_as_their_widest_cell() {
+        let data = table(
+            &["id", "name", "total"],
+            &[
+                &["1", "Alice", "12.5"],
+                &["2", "Bob", "7"],
+                &["300", "Zoë", ""],
+            ],
+        );
+        assert_eq!(
+            data.to_string(),
+            "id   name   total\n\
+             ---  -----  -----\n\
+             1    Alice  12.5\n\
+             2    Bob    7\n\
+             300  Zoë\n"
+        );
+    }
+
+    #[test]
+    fn short_rows_leave_their_columns_blank() {
+        let data = table(&["a", "b", "c"], &[&["x"], &[], &["1", "2", "3", "extra"]]);
+        assert_eq!(
+            data.to_string(),
+            "a  b  c\n\
+             -  -  -\n\
+             x\n\
+             \n\
+             1  2  3\n"
+        );
+    }
+
+    #[test]
+    fn labels_rows_and_color() {
+        let data = table(&["a"], &[&["1"], &["2"], &["3"]]);
+        let text = data
+            .table()
+            .header(vec!["Label".into()])
+            .max_rows(1)
+            .to_string();
+        assert_eq!(text, "Label\n-----\n1\n... 2 more record(s)\n");
+
+        let text = data.table().max_rows(0).color(true).to_string();
+        assert_eq!(
+            text,
+            "\x1b[1ma\x1b[0m\n\x1b[2m-\x1b[0m\n\x1b[2m... 3 more record(s)\x1b[0m\n"
+        );
+        assert_eq!(data.table().max_rows(5).to_string(), data.to_string());
+    }
+}
@@ -0,0 +1,52 @@
+//! Helpers shared by the unit tests.
+
+use std::{
+    fs,
+    path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// A file in the temporary directory, removed on drop along with
+/// `<path>.bak` and anything else [`TempFile::sibling`] named.
+pub(super) struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// A path that doesn't exist yet.
+    pub(super) fn empty(name: &str) -> Self {
+        let file = FILES.fetch_add(1, Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("custom-errors-{}-{}-{}", process::id(), file, name));
+        Self { path }
+    }
+
+    pub(super) fn new(name: &str, contents: &str) -> Self {
+        let file = Self::empty(name);
+        fs::write(&file.path, contents).unwrap();
+        file
+    }
+
+    pub(super) fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+
+    /// The path with `suffix` appended, such as `.bak`.
+    pub(super) fn sibling(&self, suffix: &str) -> String {
+        format!("{}{}", self.path(), suffix)
+    }
+
+    pub(super) fn read(&self) -> String {
+        fs::read_to_string(&self.path).unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+        let _ = fs::remove_file(self.sibling(".bak"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Splitting text into records and fields. A field that starts with the
+//! quote is quoted: up to the closing quote, delimiters and line breaks are
+//! part of its text, and a doubled quote stands for one. Text after the
+//! closing quote is kept as it is, and an unclosed quote runs to the end of
+//! the input.
+
+use alloc::borrow::Cow;
+
+use super::CsvReadOptions;
+
+/// Finds where records end, keeping track of quotes across the lines of a
+/// record.
+pub(super) struct Scanner {
+    delimiter: char,
+    quote: Option<char>,
+    in_quotes: bool,
+    field_start: bool,
+}
+
+impl Scanner {
+    pub(super) fn new(options: &CsvReadOptions) -> Self {
+        Self {
+            delimiter: options.delimiter,
+            quote: options.quote,
+            in_quotes: false,
+            field_start: true,
+        }
+    }
+
+    /// The offset of the line break that ends the current record, if it is
+    /// in `bytes`. Otherwise the record goes on past `bytes`, and the search
+    /// continues with the next call.
+    pub(super) fn record_end(&mut self, bytes: &[u8]) -> Option<usize> {
+        let Some(quote) = self.quote else {
+            return bytes.iter().position(|&b| b == b'\n');
+        };
+        let mut buf = [0; 4];
+        let quote = quote.encode_utf8(&mut buf).as_bytes();
+        let mut buf = [0; 4];
+        let delimiter = self.delimiter.encode_utf8(&mut buf).as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &bytes[i..];
+            if self.in_quotes {
+                if rest.starts_with(quote) {
+                    // A doubled quote stays inside the field.
+                    let escaped = rest[quote.len()..].starts_with(quote);
+                    self.in_quotes = escaped;
+                    i += quote.len() * (1 + usize::from(escaped));
+                    continue;
+                }
+            } else if rest[0] == b'\n' {
+                self.field_start = true;
+                return Some(i);
+            } else if self.field_start && rest.starts_with(quote) {
+                self.in_quotes = true;
+                i += quote.len();
+                self.field_start = false;
+                continue;
+            } else if rest.starts_with(delimiter) {
+                self.field_start = true;
+                i += delimiter.len();
+                continue;
+            } else {
+                self.field_start = false;
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+/// The records of `input`, without their `\n` or `\r\n`. Line breaks inside
+/// a quoted field are part of the record.
+pub(super) fn records<'a>(
+    input: &'a [u8],
+    options: &CsvReadOptions,
+) -> impl Iterator<Item = &'a [u8]> {
+    let mut scanner = Scanner::new(options);
+    let mut rest = Some(input).filter(|input| !input.is_empty());
+    core::iter::from_fn(move || {
+        let bytes = rest?;
+        let record = match scanner.record_end(bytes) {
+            Some(end) => {
+                rest = Some(&bytes[end + 1..]).filter(|rest| !rest.is_empty());
+                &bytes[..end]
+            }
+            // An unclosed quote: the record runs to the end of the input.
+            None => {
+                rest = None;
+                bytes.strip_suffix(b"\n").unwrap_or(bytes)
+            }
+        };
+        Some(record.strip_suffix(b"\r").unwrap_or(record))
+    })
+}
+
+/// The fields of a record, each with the byte offset in `line` it starts
+/// at. Quoted fields are returned without their quotes.
+pub(super) fn fields<'a>(
+    line: &'a str,
+    options: &CsvReadOptions,
+) -> impl Iterator<Item = (usize, Cow<'a, str>)> {
+    let (delimiter, quote) = (options.delimiter, options.quote);
+    let mut rest = Some(line);
+    core::iter::from_fn(move || {
+        let text = rest?;
+        let start = line.len() - text.len();
+        let (field, next) = match quote.filter(|&q| text.starts_with(q)) {
+            Some(quote) => quoted(&text[quote.len_utf8()..], quote, delimiter),
+            None => match text.find(delimiter) {
+                Some(end) => (
+                    Cow::Borrowed(&text[..end]),
+                    Some(&text[end + delimiter.len_utf8()..]),
+                ),
+                None => (Cow::Borrowed(text), None),
+            },
+        };
+        rest = next;
+        Some((start, field))
+    })
+}
+
+/// Splits a quoted field off `text`, which starts after the opening quote.
+/// Returns the field and what follows its delimiter, if there is one.
+fn quoted(text: &str, quote: char, delimiter: char) -> (Cow<'_, str>, Option<&str>) {
+    let mut field = Cow::Borrowed("");
+    let mut rest = text;
+    loop {
+        let Some(end) = rest.find(quote) else {
+            // Unclosed: the field takes the rest of the record.
+            push(&mut field, rest);
+            return (field, None);
+        };
+        push(&mut field, &rest[..end]);
+        rest = &rest[end + quote.len_utf8()..];
+        if !rest.starts_with(quote) {
+            break;
+        }
+        field.to_mut().push(quote);
+        rest = &rest[quote.len_utf8()..];
+    }
+    match rest.find(delimiter) {
+        Some(end) => {
+            push(&mut field, &rest[..end]);
+            (field, Some(&rest[end + delimiter.len_utf8()..]))
+        }
+        None => {
+            push(&mut field, rest);
+            (field, None)
+        }
+    }
+}
+
+/// Appends `text` to `field`, borrowing as long as `field` is empty.
+fn push<'a>(field: &mut Cow<'a, str>, text: &'a str) {
+    if field.is_empty() {
+        *field = Cow::Borrowed(text);
+    } else if !text.is_empty() {
+        field.to_mut().push_str(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec, vec::Vec};
+
+    use super::*;
+    use crate::csvreader::{parse_csv, CsvError, Strictness};
+
+    fn split(input: &str) -> Vec<Vec<String>> {
+        let options = CsvReadOptions::default();
+        records(input.as_bytes(), &options)
+            .map(|record| {
+                let line = core::str::from_utf8(record).unwrap();
+                fields(line, &options)
+                    .map(|(_, field)| field.into_owned())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quoted_delimiters_are_part_of_the_field() {
+        assert_eq!(split("a,\"b,c\",d"), vec![vec!["a", "b,c", "d"]]);
+    }
+
+    #[test]
+    fn doubled_quotes_stand_for_one() {
+        assert_eq!(split("\"say \"\"hi\"\"\",x"), vec![vec!["say \"hi\"", "x"]]);
+        assert_eq!(split("\"\"\"\""), vec![vec!["\""]]);
+    }
+
+    #[test]
+    fn crlf_ends_records() {
+        assert_eq!(
+            split("a,b\r\n1,2\r\n"),
+            vec![vec!["a", "b"], vec!["1", "2"]]
+        );
+    }
+
+    #[test]
+    fn line_breaks_inside_quotes_stay_in_the_field() {
+        assert_eq!(
+            split("a,\"x\r\ny\nz\"\n1,2\n"),
+            vec![vec!["a", "x\r\ny\nz"], vec!["1", "2"]]
+        );
+    }
+
+    #[test]
+    fn unclosed_quote_runs_to_the_end() {
+        assert_eq!(split("a,\"b\nc,d\n"), vec![vec!["a", "b\nc,d"]]);
+    }
+
+    #[test]
+    fn field_offsets_point_at_the_field() {
+        let options = CsvReadOptions::default();
+        let offsets: Vec<usize> = fields("ab,\"c\",d", &options).map(|(i, _)| i).collect();
+        assert_eq!(offsets, vec![0, 3, 7]);
+    }
+
+    #[test]
+    fn scanner_continues_a_quoted_field_across_chunks() {
+        let mut scanner = Scanner::new(&CsvReadOptions::default());
+        assert_eq!(scanner.record_end(b"a,\"b\n"), None);
+        assert_eq!(scanner.record_end(b"c\",d\ne"), Some(4));
+    }
+
+    #[test]
+    fn byte_order_mark_is_stripped_from_the_header() {
+        let input = b"\xEF\xBB\xBFa,b\n1,2\n";
+        let strict = parse_csv::<String>(input, &CsvReadOptions::default());
+        assert!(matches!(strict, Err(CsvError::ByteOrderMark)));
+
+        let options = CsvReadOptions::default().strictness(Strictness::Warn);
+        let (data, report) = parse_csv::<String>(input, &options).unwrap();
+        assert_eq!(data.header, vec!["a", "b"]);
+        assert!(matches!(report.warnings[..], [CsvError::ByteOrderMark]));
+    }
+}
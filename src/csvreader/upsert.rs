@@ -0,0 +1,201 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::{CsvData, CsvError, Result};
+
+/// What a [`CsvData::upsert`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UpsertReport {
+    /// Records of the table a patch record was applied to.
+    pub updated: usize,
+    /// Patch records appended as new records.
+    pub inserted: usize,
+    pub conflicts: Vec<UpsertConflict>,
+}
+
+/// A patch record whose key didn't pick out a single record. It is still
+/// applied, as described by its [`ConflictKind`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UpsertConflict {
+    /// Line of the patch record, counting the header as line 1.
+    pub line_num: usize,
+    pub key: String,
+    pub kind: ConflictKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ConflictKind {
+    /// An earlier patch record has the same key; the later one is applied
+    /// over it.
+    RepeatedInPatch { first_line: usize },
+    /// Several records of the table have the key; all of them are updated.
+    Ambiguous { records: usize },
+}
+
+impl<T: Clone + Default + Display> CsvData<T> {
+    /// Applies `patch`, such as a file of changes, to the table: each patch
+    /// record replaces the values of the records with its `key`, and is
+    /// appended if there are none. Keys are compared by their text.
+    ///
+    /// The patch may have fewer columns than the table, in any order; an
+    /// update keeps the values of the columns it doesn't have, and an
+    /// inserted record gets defaults for them. A patch column the table
+    /// doesn't have fails the upsert before anything is changed.
+    pub fn upsert(&mut self, patch: &CsvData<T>, key: &str) -> Result<UpsertReport> {
+        let position = |data: &CsvData<T>, column: &str| {
+            data.header
+                .iter()
+                .position(|name| name == column)
+                .ok_or_else(|| CsvError::UnknownColumn(column.into()))
+        };
+        // ERROR 16: key or patch column is not in the table.
+        let key_column = position(self, key)?;
+        let patch_key = position(patch, key)?;
+        let columns = patch
+            .header
+            .iter()
+            .map(|name| position(self, name))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut records: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, row) in self.data.iter().enumerate() {
+            let key = row.get(key_column).map(ToString::to_string);
+            records.entry(key.unwrap_or_default()).or_default().push(i);
+        }
+
+        let mut report = UpsertReport::default();
+        let mut applied: HashMap<String, usize> = HashMap::new();
+        for (i, patch_row) in patch.data.iter().enumerate() {
+            let line_num = i + 2;
+            let key = patch_row.get(patch_key).map(ToString::to_string);
+            let key = key.unwrap_or_default();
+            if let Some(&first_line) = applied.get(&key) {
+                report.conflicts.push(UpsertConflict {
+                    line_num,
+                    key: key.clone(),
+                    kind: ConflictKind::RepeatedInPatch { first_line },
+                });
+            } else {
+                applied.insert(key.clone(), line_num);
+            }
+
+            let targets = records.entry(key.clone()).or_default();
+            if targets.is_empty() {
+                let mut row = vec![T::default(); self.header.len()];
+                set(&mut row, patch_row, &columns);
+                targets.push(self.data.len());
+                self.data.push(row);
+                report.inserted += 1;
+                continue;
+            }
+            if targets.len() > 1 {
+                report.conflicts.push(UpsertConflict {
+                    line_num,
+                    key,
+                    kind: ConflictKind::Ambiguous {
+                        records: targets.len(),
+                    },
+                });
+            }
+            for &target in targets.iter() {
+                set(&mut self.data[target], patch_row, &columns);
+            }
+            report.updated += targets.len();
+        }
+        Ok(report)
+    }
+}
+
+/// Copies each value of `patch_row` into `row`, at the position of its
+/// column in `columns`.
+fn set<T: Clone + Default>(row: &mut Vec<T>, patch_row: &[T], columns: &[usize]) {
+    for (value, &i) in patch_row.iter().zip(columns) {
+        if row.len() <= i {
+            row.resize(i + 1, T::default());
+        }
+        row[i] = value.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(header: &[&str], rows: &[&[&str]]) -> CsvData<String> {
+        CsvData {
+            header: header.iter().map(|name| name.to_string()).collect(),
+            data: rows
+                .iter()
+                .map(|row| row.iter().map(|v| v.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn updates_and_inserts() {
+        let mut data = table(
+            &["id", "qty", "note"],
+            &[&["1", "5", "a"], &["2", "6", "b"]],
+        );
+        let patch = table(&["qty", "id"], &[&["7", "2"], &["8", "3"]]);
+        let report = data.upsert(&patch, "id").unwrap();
+        assert_eq!(
+            report,
+            UpsertReport {
+                updated: 1,
+                inserted: 1,
+                conflicts: vec![]
+            }
+        );
+        assert_eq!(
+            data.data,
+            [["1", "5", "a"], ["2", "7", "b"], ["3", "8", ""]]
+        );
+    }
+
+    #[test]
+    fn duplicate_keys_are_conflicts() {
+        let mut data = table(&["id", "qty"], &[&["1", "5"], &["1", "6"], &["2", "0"]]);
+        let patch = table(&["id", "qty"], &[&["1", "7"], &["2", "8"], &["2", "9"]]);
+        let report = data.upsert(&patch, "id").unwrap();
+        assert_eq!(report.updated, 4);
+        assert_eq!(
+            report.conflicts,
+            [
+                UpsertConflict {
+                    line_num: 2,
+                    key: "1".into(),
+                    kind: ConflictKind::Ambiguous { records: 2 },
+                },
+                UpsertConflict {
+                    line_num: 4,
+                    key: "2".into(),
+                    kind: ConflictKind::RepeatedInPatch { first_line: 3 },
+                },
+            ]
+        );
+        assert_eq!(data.data, [["1", "7"], ["1", "7"], ["2", "9"]]);
+    }
+
+    #[test]
+    fn short_records_are_extended() {
+        let mut data = table(&["id", "qty", "note"], &[&["1"], &[]]);
+        let patch = table(&["id", "note"], &[&["1", "x"], &["", "y"]]);
+        let report = data.upsert(&patch, "id").unwrap();
+        assert_eq!((report.updated, report.inserted), (2, 0));
+        assert_eq!(data.data, [vec!["1", "", "x"], vec!["", "", "y"]]);
+    }
+
+    #[test]
+    fn unknown_patch_columns_change_nothing() {
+        let mut data = table(&["id", "qty"], &[&["1", "5"]]);
+        let patch = table(&["id", "price"], &[&["1", "2"]]);
+        assert!(matches!(
+            data.upsert(&patch, "id"),
+            Err(CsvError::UnknownColumn(c)) if c == "price"
+        ));
+        assert_eq!(data.data, [["1", "5"]]);
+    }
+}
@@ -0,0 +1,136 @@
+use alloc::{format, string::String};
+use core::{convert::Infallible, fmt, str::FromStr};
+
+/// The type of a column, as declared in a [`CsvSchema`](super::CsvSchema).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(
+    any(feature = "config", feature = "serde"),
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ColumnType {
+    Bool,
+    Int,
+    Float,
+    Text,
+}
+
+impl ColumnType {
+    /// Parses non-empty `text` as this type.
+    pub fn parse(self, text: &str) -> Option<Value> {
+        match self {
+            Self::Bool => match text.to_ascii_lowercase().as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            Self::Int => text.parse().ok().map(Value::Int),
+            Self::Float => text.parse().ok().map(Value::Float),
+            Self::Text => Some(Value::Text(text.into())),
+        }
+    }
+
+    /// The narrowest type both `self` and `other` values fit.
+    #[cfg(feature = "std")]
+    pub(super) fn widen(self, other: Self) -> Self {
+        match (self, other) {
+            _ if self == other => self,
+            (Self::Int, Self::Float) | (Self::Float, Self::Int) => Self::Float,
+            _ => Self::Text,
+        }
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bool => "bool",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Text => "text",
+        })
+    }
+}
+
+impl FromStr for ColumnType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bool" => Ok(Self::Bool),
+            "int" => Ok(Self::Int),
+            "float" => Ok(Self::Float),
+            "text" => Ok(Self::Text),
+            _ => Err(format!("unknown column type {:?}", s)),
+        }
+    }
+}
+
+/// A single cell of a table whose columns have different types.
+///
+/// Parsing a `Value` from a string infers the type: empty is `Null`, then
+/// `Bool`, `Int` and `Float` are tried before falling back to `Text`.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "cache", derive(bincode::Encode, bincode::Decode))]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// `None` for `Null`.
+    pub fn column_type(&self) -> Option<ColumnType> {
+        match self {
+            Self::Null => None,
+            Self::Bool(_) => Some(ColumnType::Bool),
+            Self::Int(_) => Some(ColumnType::Int),
+            Self::Float(_) => Some(ColumnType::Float),
+            Self::Text(_) => Some(ColumnType::Text),
+        }
+    }
+
+    /// Numeric values as `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(i) => Some(*i as f64),
+            Self::Float(x) => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self::Null);
+        }
+        let value = [ColumnType::Bool, ColumnType::Int, ColumnType::Float]
+            .into_iter()
+            .find_map(|ty| ty.parse(s))
+            .unwrap_or_else(|| Self::Text(s.into()));
+        Ok(value)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => Ok(()),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Int(i) => write!(f, "{}", i),
+            Self::Float(x) => write!(f, "{}", x),
+            Self::Text(s) => f.write_str(s),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    io::{self, BufReader},
+    path::Path,
+    str::FromStr,
+};
+
+use calamine::{Data, Reader, Xlsx, XlsxError};
+
+use super::{
+    file::open_file,
+    rows::{parse_rows, row_line},
+    CsvData, CsvError, CsvErrorReport, CsvFileError, CsvReadOptions, FieldParser, FromStrParser,
+    Result,
+};
+
+/// Reads the worksheet named `sheet` from the Excel workbook at `path`, as
+/// [`read_csv`](super::read_csv) reads a file: each row is a record, and
+/// each cell is parsed from its text. Dates read as `2024-03-01` or
+/// `2024-03-01T09:30:00`, formula errors as their text, such as `#DIV/0!`.
+///
+/// Problems are reported with the row number as their line number.
+pub fn read_xlsx<T: Default + FromStr>(path: &str, sheet: &str) -> Result<CsvData<T>> {
+    read_xlsx_with_options(path, sheet, &CsvReadOptions::default()).map(|(data, _)| data)
+}
+
+/// [`read_xlsx`] with explicit options, see
+/// [`read_csv_with_options`](super::read_csv_with_options). The delimiter
+/// and quote don't apply.
+pub fn read_xlsx_with_options<T: Default + FromStr>(
+    path: &str,
+    sheet: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    read(path, sheet, options, false, &mut FromStrParser)
+}
+
+/// [`read_xlsx`] in collect-all mode, see
+/// [`read_csv_validated`](super::read_csv_validated).
+pub fn read_xlsx_validated<T: Default + FromStr>(
+    path: &str,
+    sheet: &str,
+    options: &CsvReadOptions,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    read(path, sheet, options, true, &mut FromStrParser)
+}
+
+fn read<T: Default>(
+    path: &str,
+    sheet: &str,
+    options: &CsvReadOptions,
+    collect_errors: bool,
+    parser: &mut dyn FieldParser<T>,
+) -> Result<(CsvData<T>, CsvErrorReport)> {
+    let file = open_file(path)?;
+    let range = Xlsx::new(BufReader::new(file))
+        .and_then(|mut workbook| workbook.worksheet_range(sheet))
+        .map_err(|e| workbook_error(Path::new(path), e))?;
+
+    let lines: Vec<Vec<u8>> = range
+        .rows()
+        .map(|row| row_line(row.iter().map(cell_text)))
+        .collect();
+    // Records are numbered by their row in the sheet.
+    let first_row = range.start().map_or(1, |(row, _)| row as usize + 1);
+    parse_rows(&lines, |i| first_row + i, options, collect_errors, parser)
+}
+
+/// The text of a cell, as it would be written to a CSV file.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(dt) if dt.is_datetime() => {
+            let (year, month, day, hour, minute, second, milli) = dt.to_ymd_hms_milli();
+            let date = format!("{:04}-{:02}-{:02}", year, month, day);
+            match (hour, minute, second, milli) {
+                (0, 0, 0, 0) => date,
+                (_, _, _, 0) => format!("{}T{:02}:{:02}:{:02}", date, hour, minute, second),
+                _ => format!(
+                    "{}T{:02}:{:02}:{:02}.{:03}",
+                    date, hour, minute, second, milli
+                ),
+            }
+        }
+        cell => cell.to_string(),
+    }
+}
+
+/// Maps a failure to read the workbook to the matching [`CsvError`].
+fn workbook_error(path: &Path, e: XlsxError) -> CsvError {
+    match e {
+        // ERROR 1: file could not be read.
+        XlsxError::Io(e) => CsvFileError::into_error(path, e),
+        // ERROR 18: there is no such sheet.
+        XlsxError::WorksheetNotFound(sheet) => CsvError::UnknownSheet(sheet),
+        // ERROR 1: file is not a workbook.
+        e => CsvError::CouldNotOpenFile(CsvFileError {
+            path: path.to_path_buf(),
+            source: io::Error::new(io::ErrorKind::InvalidData, e),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use calamine::{CellErrorType, ExcelDateTime, ExcelDateTimeType};
+
+    use super::*;
+    use crate::csvreader::testutil::TempFile;
+
+    fn date_time(value: f64) -> Data {
+        Data::DateTime(ExcelDateTime::new(
+            value,
+            ExcelDateTimeType::DateTime,
+            false,
+        ))
+    }
+
+    #[test]
+    fn cells_read_as_the_text_of_their_value() {
+        assert_eq!(cell_text(&Data::Float(2.5)), "2.5");
+        // Excel keeps every number as a float; whole ones read as integers.
+        assert_eq!(cell_text(&Data::Float(3.0)), "3");
+        assert_eq!(cell_text(&Data::Int(-7)), "-7");
+        assert_eq!(cell_text(&Data::Bool(true)), "true");
+        assert_eq!(cell_text(&Data::String("a,\"b\"".into())), "a,\"b\"");
+        assert_eq!(cell_text(&Data::Empty), "");
+        assert_eq!(cell_text(&Data::Error(CellErrorType::Div0)), "#DIV/0!");
+        assert_eq!(cell_text(&Data::Error(CellErrorType::NA)), "#N/A");
+    }
+
+    #[test]
+    fn dates_read_as_iso_8601() {
+        assert_eq!(cell_text(&date_time(45352.0)), "2024-03-01");
+        assert_eq!(
+            cell_text(&date_time(45352.0 + 9.5 / 24.0)),
+            "2024-03-01T09:30:00"
+        );
+        let millis = 45352.0 + (9.5 * 3600.0 + 1.25) / 86400.0;
+        assert_eq!(cell_text(&date_time(millis)), "2024-03-01T09:30:01.250");
+    }
+
+    #[test]
+    fn a_file_that_is_not_a_workbook_cannot_be_opened() {
+        let file = TempFile::new("not_a_workbook.xlsx", "a,b\n1,2\n");
+        match read_xlsx::<String>(file.path(), "Sheet1") {
+            Err(CsvError::CouldNotOpenFile(e)) => {
+                assert_eq!(e.source.kind(), io::ErrorKind::InvalidData)
+            }
+            other => panic!("expected an open error, got {:?}", other),
+        }
+        let missing = TempFile::empty("missing.xlsx");
+        assert!(matches!(
+            read_xlsx::<String>(missing.path(), "Sheet1"),
+            Err(CsvError::FileNotFound(_))
+        ));
+    }
+}
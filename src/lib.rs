@@ -0,0 +1,28 @@
+//! Reading, checking and writing CSV files, with errors that say what went
+//! wrong and where.
+//!
+//! Everything lives in [`csvreader`]; the `csvtool` binary in this
+//! workspace is a command-line front end to it.
+//!
+//! The reader itself has no dependencies. Integrations are opt-in features:
+//!
+//! | feature    | adds                                                 |
+//! |------------|------------------------------------------------------|
+//! | `std`      | reading files; needed by all of the others (default) |
+//! | `arrow`    | Arrow record batches and IPC (Feather) files         |
+//! | `cache`    | caching parsed tables on disk                        |
+//! | `calamine` | reading Excel workbooks                              |
+//! | `config`   | options and schemas from TOML and YAML files         |
+//! | `derive`   | `#[derive(CsvRecord)]`                               |
+//! | `rayon`    | mapping the records of a table on every core         |
+//! | `regex`    | `pattern` constraints                                |
+//! | `serde`    | serializing tables, reports and schemas; JSON I/O    |
+//! | `sqlite`   | tables from SQLite query results                     |
+//! | `tracing`  | spans and events for reads                           |
+//! | `full`     | all of the above                                     |
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod csvreader;